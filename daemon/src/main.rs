@@ -4,19 +4,30 @@
 //! Supports inotify for real-time local updates and periodic scanning for network mounts.
 
 use std::collections::{HashMap, HashSet};
-use std::io::{BufRead, BufReader, Write};
-use std::os::unix::net::{UnixListener, UnixStream};
+use std::io::{Read, Seek};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{fs, thread};
-use std::sync::mpsc::{channel, Sender};
 
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher, Event};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher, Event};
+use rayon::prelude::*;
+use regex::Regex;
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, RwLock as AsyncRwLock};
 use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
+use xxhash_rust::xxh3::Xxh3;
+
+use bytes_cast::{unaligned::{U32Le, U64Le}, BytesCast};
+use memmap2::Mmap;
+
+use lofty::{AudioFile, TaggedFileExt, Accessor, Probe};
 
 // Database operations sent through a channel to serialize access
 enum DbOp {
@@ -24,6 +35,16 @@ enum DbOp {
     RemoveFile(String),
     SaveBookmark(Bookmark),
     ClearFilesUnder(String),
+    SaveHash { path: String, mtime: i64, size: u64, hash: String },
+    /// A batch of files from a single scan pass, persisted in one transaction.
+    SaveBatch(Vec<FileEntry>),
+    /// Extracted metadata (MIME, EXIF, tags, ...) for a single file.
+    SaveAttributes { path: String, attrs: HashMap<String, AttrValue> },
+    /// Snapshot of a `TaskStore` entry's current status, persisted so task
+    /// history survives a daemon restart.
+    SaveTask(TaskReport),
+    /// Result of a corruption check (see `check_file_health`) for a single file.
+    SaveFileHealth { path: String, health: FileHealth },
 }
 
 // ============================================================================
@@ -32,6 +53,7 @@ enum DbOp {
 
 const SOCKET_PATH: &str = "/run/user/1000/nixnav-daemon.sock";
 const DB_PATH: &str = ".local/share/nixnav/index.db";
+const SNAPSHOT_PATH: &str = ".local/share/nixnav/index.snapshot";
 const MAX_RESULTS: usize = 2000;
 const NETWORK_SCAN_INTERVAL_SECS: u64 = 300; // 5 minutes
 
@@ -54,6 +76,177 @@ const EXCLUDE_PATTERNS: &[&str] = &[
     "target", "build", "dist", ".next", ".nuxt", ".Trash", "Trash",
 ];
 
+const CONFIG_PATH: &str = ".config/nixnav/daemon.conf";
+const MAX_CONFIG_INCLUDE_DEPTH: usize = 8;
+
+/// Resolved runtime configuration, loaded from `~/.config/nixnav/daemon.conf`
+/// (or the built-in defaults above when that file doesn't exist).
+#[derive(Debug, Clone)]
+struct Config {
+    socket_path: String,
+    db_path: String,
+    max_results: usize,
+    network_scan_interval_secs: u64,
+    binary_extensions: Vec<String>,
+    exclude_patterns: Vec<String>,
+    /// Opt-in periodic corrupt/broken-file scan (see `start_health_checker`).
+    /// Off by default since the format-specific checks are heavier than the
+    /// plain `path.exists()` check `start_integrity_checker` does.
+    health_check_enabled: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            socket_path: SOCKET_PATH.to_string(),
+            db_path: DB_PATH.to_string(),
+            max_results: MAX_RESULTS,
+            network_scan_interval_secs: NETWORK_SCAN_INTERVAL_SECS,
+            binary_extensions: BINARY_EXTENSIONS.iter().map(|s| s.to_string()).collect(),
+            exclude_patterns: EXCLUDE_PATTERNS.iter().map(|s| s.to_string()).collect(),
+            health_check_enabled: false,
+        }
+    }
+}
+
+/// Accumulated raw values while parsing the config file and any `%include`s.
+/// List-valued keys (e.g. `exclude.pattern`) accumulate across files; a
+/// `%unset` can later remove the whole key or just one accumulated value.
+struct RawConfig {
+    values: HashMap<String, Vec<String>>,
+}
+
+fn qualify_config_key(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", section, key)
+    }
+}
+
+fn expand_config_path(raw: &str) -> PathBuf {
+    let raw = raw.trim();
+    if let Some(rest) = raw.strip_prefix("~/") {
+        dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| PathBuf::from(raw))
+    } else {
+        PathBuf::from(raw)
+    }
+}
+
+/// Parse one ini-style config file into `raw`, following `%include` directives
+/// (up to `MAX_CONFIG_INCLUDE_DEPTH` deep to guard against include cycles).
+fn parse_config_file(path: &Path, raw: &mut RawConfig, depth: usize) {
+    if depth > MAX_CONFIG_INCLUDE_DEPTH {
+        warn!("Config %include nesting too deep, skipping {}", path.display());
+        return;
+    }
+
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to read config file {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    let section_re = Regex::new(r"^\[(?P<name>[A-Za-z0-9_.-]+)\]$").unwrap();
+    let kv_re = Regex::new(r"^(?P<key>[A-Za-z0-9_.-]+)\s*=\s*(?P<value>.*)$").unwrap();
+    let include_re = Regex::new(r"^%include\s+(?P<path>.+)$").unwrap();
+    let unset_re = Regex::new(r"^%unset\s+(?P<key>[A-Za-z0-9_.-]+)(?:\s*=\s*(?P<value>.+))?$").unwrap();
+
+    let mut section = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(caps) = section_re.captures(line) {
+            section = caps["name"].to_string();
+        } else if let Some(caps) = include_re.captures(line) {
+            let included = expand_config_path(&caps["path"]);
+            parse_config_file(&included, raw, depth + 1);
+        } else if let Some(caps) = unset_re.captures(line) {
+            let key = qualify_config_key(&section, &caps["key"]);
+            match caps.name("value") {
+                Some(value) => {
+                    if let Some(list) = raw.values.get_mut(&key) {
+                        list.retain(|v| v != value.as_str());
+                    }
+                }
+                None => {
+                    raw.values.remove(&key);
+                }
+            }
+        } else if let Some(caps) = kv_re.captures(line) {
+            let key = qualify_config_key(&section, &caps["key"]);
+            raw.values.entry(key).or_default().push(caps["value"].trim().to_string());
+        } else {
+            warn!("Unrecognized line in config {}: {}", path.display(), line);
+        }
+    }
+}
+
+/// Load `~/.config/nixnav/daemon.conf`, falling back to built-in defaults
+/// when it doesn't exist.
+fn load_config() -> Config {
+    let mut config = Config::default();
+
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return config,
+    };
+    let path = home.join(CONFIG_PATH);
+    if !path.exists() {
+        return config;
+    }
+
+    // Seed the accumulating lists with the built-in defaults so a user file
+    // can `%unset` one of them, not just add to them.
+    let mut raw = RawConfig {
+        values: HashMap::new(),
+    };
+    raw.values.insert(
+        "exclude.pattern".to_string(),
+        config.exclude_patterns.clone(),
+    );
+    raw.values.insert(
+        "binary_extensions.ext".to_string(),
+        config.binary_extensions.clone(),
+    );
+
+    parse_config_file(&path, &mut raw, 0);
+
+    let scalar = |key: &str| -> Option<&String> {
+        raw.values
+            .get(&format!("daemon.{}", key))
+            .or_else(|| raw.values.get(key))
+            .and_then(|v| v.last())
+    };
+
+    if let Some(v) = scalar("socket_path") {
+        config.socket_path = v.clone();
+    }
+    if let Some(v) = scalar("db_path") {
+        config.db_path = v.clone();
+    }
+    if let Some(v) = scalar("max_results").and_then(|v| v.parse().ok()) {
+        config.max_results = v;
+    }
+    if let Some(v) = scalar("network_scan_interval_secs").and_then(|v| v.parse().ok()) {
+        config.network_scan_interval_secs = v;
+    }
+    if let Some(v) = scalar("health_check_enabled").and_then(|v| v.parse().ok()) {
+        config.health_check_enabled = v;
+    }
+
+    config.exclude_patterns = raw.values.remove("exclude.pattern").unwrap_or_default();
+    config.binary_extensions = raw.values.remove("binary_extensions.ext").unwrap_or_default();
+
+    config
+}
+
 // ============================================================================
 // Data Structures
 // ============================================================================
@@ -65,6 +258,10 @@ struct FileEntry {
     is_dir: bool,
     mtime: i64,
     size: u64,
+    /// Cheap content fingerprint (see `compute_fingerprint`), used to spot
+    /// moved/renamed files without a full hash. `None` for directories and
+    /// for entries loaded from the mmapped snapshot, where it isn't persisted.
+    fingerprint: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +277,68 @@ struct SearchRequest {
     mode: String,  // "edit", "gotofile", "gotodir", "all"
     query: String,
     extension: Option<String>,
+    /// "relevance" (default) ranks by match quality; "mtime" opts back into
+    /// pure recency ordering.
+    #[serde(default = "default_sort")]
+    sort: String,
+    /// Require the extracted `FILE_MIME` attribute to start with this prefix
+    /// (e.g. `"audio/"` or `"image/"`).
+    #[serde(default)]
+    mime_prefix: Option<String>,
+    /// Extracted-attribute filters, ANDed together and intersected with the
+    /// trigram search hits. A file with no attributes at all never matches
+    /// a request with a non-empty `attr_filters`.
+    #[serde(default)]
+    attr_filters: Vec<AttrFilter>,
+}
+
+/// A value extracted by the metadata extractors (see `extract_attributes`),
+/// or supplied in an `AttrFilter` to compare against one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum AttrValue {
+    Text(String),
+    Number(f64),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AttrOp {
+    Eq,
+    Gte,
+    Lte,
+}
+
+/// Result of a lightweight corruption check for a single file (see
+/// `check_file_health`), keyed by the `(size, mtime)` it was validated
+/// against so unchanged files aren't re-validated every pass - mirrors
+/// czkawka's cache-folder optimization for its `broken_files` scan.
+/// `error_string` is `None` for a file that passed the check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileHealth {
+    type_of_file: String,
+    error_string: Option<String>,
+    checked_size: u64,
+    checked_mtime: i64,
+}
+
+/// One `LIST_BROKEN` result entry.
+#[derive(Debug, Clone, Serialize)]
+struct BrokenFileEntry {
+    path: String,
+    type_of_file: String,
+    error_string: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AttrFilter {
+    key: String,
+    op: AttrOp,
+    value: AttrValue,
+}
+
+fn default_sort() -> String {
+    "relevance".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +361,11 @@ struct SearchResult {
     path: String,
     is_dir: bool,
     mtime: i64,
+    /// Set when the health checker (see `check_file_health`) has flagged this
+    /// file as corrupt. `None` both for unchecked files and for files that
+    /// passed the check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_string: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -111,6 +375,34 @@ struct SearchResponse {
     search_time_ms: u64,
 }
 
+/// Which algorithm to use when hashing file contents for duplicate detection.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum Hasher {
+    /// Fast, non-cryptographic. Good default for "are these probably the same file".
+    Xxh3,
+    /// Cryptographic, slower. Use when certainty matters more than speed.
+    Blake3,
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Hasher::Xxh3
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FindDuplicatesRequest {
+    bookmark_paths: Vec<String>,
+    #[serde(default)]
+    hasher: Hasher,
+}
+
+#[derive(Debug, Serialize)]
+struct DuplicateGroup {
+    size: u64,
+    paths: Vec<String>,
+}
+
 /// Trigram index for fast substring search
 struct TrigramIndex {
     /// Trigram -> set of file IDs containing this trigram
@@ -123,17 +415,186 @@ struct TrigramIndex {
     next_id: u32,
     /// Bookmarks being indexed
     bookmarks: Vec<Bookmark>,
+    /// Zero-copy mmapped posting lists not yet promoted into `trigrams`.
+    /// `None` when no snapshot was loaded; kept after every trigram has been
+    /// promoted.
+    snapshot: Option<IndexSnapshot>,
+    /// Resolved runtime config (binary extensions, result cap, ...).
+    config: Arc<Config>,
+    /// Fingerprint -> set of file IDs sharing it. Used to recognize a moved
+    /// file by content instead of just losing the old entry on a `Remove`.
+    fingerprints: HashMap<u64, HashSet<u32>>,
+    /// Fingerprints of entries removed very recently, keyed by fingerprint so
+    /// a `Create` that follows a `Remove` can be recognized as a move instead
+    /// of a fresh file. Bounded by `MAX_PENDING_REMOVALS` since not every
+    /// removal is followed by a matching create.
+    pending_removals: HashMap<u64, String>,
+    /// File ID -> extracted attributes (MIME, EXIF, tags, ...), populated
+    /// asynchronously by the `ExtractorPool` well after the file itself is
+    /// indexed. Absent until extraction finishes.
+    attrs: HashMap<u32, HashMap<String, AttrValue>>,
+    /// File ID -> corruption-check result, populated by the opt-in health
+    /// checker (see `start_health_checker`). Absent for directories and for
+    /// files whose extension/MIME isn't a known broken-file candidate kind.
+    health: HashMap<u32, FileHealth>,
+}
+
+// ============================================================================
+// Relevance Scoring
+// ============================================================================
+
+/// If the exact-substring tier returns fewer results than this, widen the
+/// search to typo-tolerant (edit-distance) matches.
+const TYPO_TIER_THRESHOLD: usize = 10;
+/// Maximum Damerau-Levenshtein distance (on the filename) to consider a typo match.
+const TYPO_MAX_DISTANCE: usize = 2;
+/// Bottom of the score band for typo matches (a typo match scores at most
+/// `TYPO_MAX_DISTANCE * 100` above it), kept well below the minimum score an
+/// exact substring match can get so typo results always sort last.
+const TYPO_SCORE_BASE: i64 = -1_000_000;
+
+/// Score how well `path`'s filename matches `query_lower` (already lowercased).
+/// Higher is better. Exact substring matches always score far above the typo
+/// band (see `TYPO_SCORE_BASE`); basename matches score far higher than matches buried in a parent directory.
+fn score_match(path: &str, query_lower: &str) -> i64 {
+    let filename = Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+        .to_lowercase();
+    let depth = path.matches('/').count() as i64;
+
+    let mut score: i64 = 0;
+
+    if let Some(pos) = filename.find(query_lower) {
+        // Matched the basename itself (not just a parent directory component).
+        score += 1000;
+        if pos == 0 {
+            // Prefix of the filename - the strongest possible signal.
+            score += 500;
+        }
+        // Earlier in the filename is better; a literal substring match is
+        // always contiguous, so it doesn't need a separate bonus for that.
+        score -= pos as i64;
+    } else {
+        // Query only matched a path component above the filename.
+        score += 100;
+    }
+
+    // Shallower paths rank slightly higher than deeply nested ones.
+    score -= depth * 5;
+
+    score
+}
+
+/// Score a typo-tolerant candidate, or `None` if it's not within
+/// `TYPO_MAX_DISTANCE` of the query. Always scores below `score_match`'s
+/// exact-match tier.
+fn typo_score(path: &str, query_lower: &str) -> Option<i64> {
+    let filename = Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+        .to_lowercase();
+
+    let distance = damerau_levenshtein(&filename, query_lower);
+    if distance > TYPO_MAX_DISTANCE {
+        return None;
+    }
+
+    Some(TYPO_SCORE_BASE + (TYPO_MAX_DISTANCE - distance) as i64 * 100)
+}
+
+/// Evaluate a single `AttrFilter` against an extracted attribute value.
+/// Mismatched value types (e.g. `Gte` against two `Text` values) never match
+/// rather than panicking - a malformed filter just returns no results.
+fn attr_matches(op: &AttrOp, actual: &AttrValue, expected: &AttrValue) -> bool {
+    match (actual, expected) {
+        (AttrValue::Text(a), AttrValue::Text(b)) => matches!(op, AttrOp::Eq) && a == b,
+        (AttrValue::Number(a), AttrValue::Number(b)) => match op {
+            AttrOp::Eq => a == b,
+            AttrOp::Gte => a >= b,
+            AttrOp::Lte => a <= b,
+        },
+        _ => false,
+    }
+}
+
+/// Damerau-Levenshtein edit distance (insertions, deletions, substitutions,
+/// and adjacent transpositions) between two strings.
+fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (la, lb) = (a.len(), b.len());
+
+    if la == 0 {
+        return lb;
+    }
+    if lb == 0 {
+        return la;
+    }
+
+    let mut d = vec![vec![0usize; lb + 1]; la + 1];
+    for (i, row) in d.iter_mut().enumerate().take(la + 1) {
+        row[0] = i;
+    }
+    for j in 0..=lb {
+        d[0][j] = j;
+    }
+
+    for i in 1..=la {
+        for j in 1..=lb {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[la][lb]
 }
 
 impl TrigramIndex {
-    fn new() -> Self {
+    fn new(config: Arc<Config>) -> Self {
         Self {
             trigrams: HashMap::new(),
             files: HashMap::new(),
             path_to_id: HashMap::new(),
             next_id: 1,
             bookmarks: Vec::new(),
+            snapshot: None,
+            config,
+            fingerprints: HashMap::new(),
+            pending_removals: HashMap::new(),
+            attrs: HashMap::new(),
+            health: HashMap::new(),
+        }
+    }
+
+    /// Posting list for a trigram, whether it lives in the mutable `HashMap`
+    /// (already promoted, or added after boot) or still only in the mmapped
+    /// snapshot.
+    fn posting_list(&self, trigram: &[u8; 3]) -> Option<HashSet<u32>> {
+        if let Some(set) = self.trigrams.get(trigram) {
+            return Some(set.clone());
+        }
+        self.snapshot.as_ref().and_then(|s| s.decode_posting_list(trigram))
+    }
+
+    /// Move a trigram's posting list out of the mmap and into the mutable
+    /// `HashMap` so it can be edited in place. A no-op once a trigram has
+    /// already been promoted; a trigram absent from the snapshot gets an
+    /// empty posting list.
+    fn promote(&mut self, trigram: [u8; 3]) {
+        if self.trigrams.contains_key(&trigram) {
+            return;
         }
+        let existing = self.snapshot.as_ref().and_then(|s| s.decode_posting_list(&trigram));
+        self.trigrams.insert(trigram, existing.unwrap_or_default());
     }
 
     /// Extract trigrams from a string (lowercase for case-insensitive search)
@@ -149,13 +610,26 @@ impl TrigramIndex {
     }
 
     /// Add a file to the index
-    fn add(&mut self, path: String, is_dir: bool, mtime: i64, size: u64) -> u32 {
+    fn add(&mut self, path: String, is_dir: bool, mtime: i64, size: u64, fingerprint: Option<u64>) -> u32 {
         // Check if already exists
         if let Some(&existing_id) = self.path_to_id.get(&path) {
             // Update existing entry
             if let Some(entry) = self.files.get_mut(&existing_id) {
                 entry.mtime = mtime;
                 entry.size = size;
+                if let Some(old_fp) = entry.fingerprint {
+                    if Some(old_fp) != fingerprint {
+                        if let Some(set) = self.fingerprints.get_mut(&old_fp) {
+                            set.remove(&existing_id);
+                        }
+                    }
+                }
+                if entry.fingerprint != fingerprint {
+                    entry.fingerprint = fingerprint;
+                    if let Some(fp) = fingerprint {
+                        self.fingerprints.entry(fp).or_default().insert(existing_id);
+                    }
+                }
             }
             return existing_id;
         }
@@ -169,19 +643,26 @@ impl TrigramIndex {
             .and_then(|n| n.to_str())
             .unwrap_or(&path);
 
-        // Index trigrams from filename
+        // Index trigrams from filename. A mutation promotes the trigram's
+        // posting list out of the mmapped snapshot (if any) first.
         for trigram in Self::extract_trigrams(filename) {
+            self.promote(trigram);
             self.trigrams.entry(trigram).or_default().insert(id);
         }
 
         // Also index path components for path-based search
         for component in path.split('/').filter(|s| !s.is_empty()) {
             for trigram in Self::extract_trigrams(component) {
+                self.promote(trigram);
                 self.trigrams.entry(trigram).or_default().insert(id);
             }
         }
 
-        let entry = FileEntry { id, path: path.clone(), is_dir, mtime, size };
+        if let Some(fp) = fingerprint {
+            self.fingerprints.entry(fp).or_default().insert(id);
+        }
+
+        let entry = FileEntry { id, path: path.clone(), is_dir, mtime, size, fingerprint };
         self.files.insert(id, entry);
         self.path_to_id.insert(path, id);
 
@@ -197,21 +678,96 @@ impl TrigramIndex {
                     .and_then(|n| n.to_str())
                     .unwrap_or(&entry.path);
 
-                // Remove from trigram index
+                // Remove from trigram index, promoting from the snapshot first
+                // so the removal actually sticks instead of being shadowed by
+                // a stale mmapped posting list.
                 for trigram in Self::extract_trigrams(filename) {
+                    self.promote(trigram);
                     if let Some(set) = self.trigrams.get_mut(&trigram) {
                         set.remove(&id);
                     }
                 }
                 for component in entry.path.split('/').filter(|s| !s.is_empty()) {
                     for trigram in Self::extract_trigrams(component) {
+                        self.promote(trigram);
                         if let Some(set) = self.trigrams.get_mut(&trigram) {
                             set.remove(&id);
                         }
                     }
                 }
+
+                if let Some(fp) = entry.fingerprint {
+                    if let Some(set) = self.fingerprints.get_mut(&fp) {
+                        set.remove(&id);
+                    }
+                }
+
+                self.attrs.remove(&id);
+            }
+        }
+    }
+
+    /// Look up another currently-indexed path sharing `fingerprint`, other
+    /// than `exclude_path` itself. Used to recognize a rename/move by content.
+    fn find_by_fingerprint(&self, fingerprint: u64, exclude_path: &str) -> Option<String> {
+        let ids = self.fingerprints.get(&fingerprint)?;
+        ids.iter()
+            .filter_map(|id| self.files.get(id))
+            .find(|entry| entry.path != exclude_path)
+            .map(|entry| entry.path.clone())
+    }
+
+    /// Move an indexed entry from `old_path` to `new_path` in place, keeping
+    /// its file ID instead of deleting and re-adding under a new one. Called
+    /// by the watcher when fingerprint matching pairs a `Create` with an
+    /// earlier `Remove`; returns `None` when `old_path` is no longer indexed.
+    fn rename(&mut self, old_path: &str, new_path: String, mtime: i64, size: u64) -> Option<u32> {
+        let id = self.path_to_id.remove(old_path)?;
+        let mut entry = self.files.remove(&id)?;
+
+        let old_filename = Path::new(&entry.path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&entry.path)
+            .to_string();
+        for trigram in Self::extract_trigrams(&old_filename) {
+            self.promote(trigram);
+            if let Some(set) = self.trigrams.get_mut(&trigram) {
+                set.remove(&id);
+            }
+        }
+        for component in entry.path.clone().split('/').filter(|s| !s.is_empty()) {
+            for trigram in Self::extract_trigrams(component) {
+                self.promote(trigram);
+                if let Some(set) = self.trigrams.get_mut(&trigram) {
+                    set.remove(&id);
+                }
+            }
+        }
+
+        let new_filename = Path::new(&new_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&new_path)
+            .to_string();
+        for trigram in Self::extract_trigrams(&new_filename) {
+            self.promote(trigram);
+            self.trigrams.entry(trigram).or_default().insert(id);
+        }
+        for component in new_path.split('/').filter(|s| !s.is_empty()) {
+            for trigram in Self::extract_trigrams(component) {
+                self.promote(trigram);
+                self.trigrams.entry(trigram).or_default().insert(id);
             }
         }
+
+        entry.path = new_path.clone();
+        entry.mtime = mtime;
+        entry.size = size;
+        self.path_to_id.insert(new_path, id);
+        self.files.insert(id, entry);
+
+        Some(id)
     }
 
     /// Search for files matching the query
@@ -227,14 +783,11 @@ impl TrigramIndex {
             // Intersect posting lists for all trigrams
             let mut iter = trigrams.iter();
             let first = iter.next().unwrap();
-            let mut candidates = self.trigrams
-                .get(first)
-                .cloned()
-                .unwrap_or_default();
+            let mut candidates = self.posting_list(first).unwrap_or_default();
 
             for trigram in iter {
-                if let Some(set) = self.trigrams.get(trigram) {
-                    candidates = candidates.intersection(set).copied().collect();
+                if let Some(set) = self.posting_list(trigram) {
+                    candidates = candidates.intersection(&set).copied().collect();
                 } else {
                     // Trigram not found - no matches
                     return Vec::new();
@@ -252,69 +805,112 @@ impl TrigramIndex {
         let is_all_mode = req.mode == "all";
         let bookmark_path = &req.bookmark_path;
 
-        let mut results: Vec<SearchResult> = candidates
-            .into_iter()
-            .filter_map(|id| self.files.get(&id))
-            .filter(|entry| {
-                // Must be under the bookmark path
-                if !entry.path.starts_with(bookmark_path) {
+        let matches_filters = |id: u32, entry: &FileEntry| -> bool {
+            // Must be under the bookmark path
+            if !entry.path.starts_with(bookmark_path) {
+                return false;
+            }
+
+            // Mode filter (skip for "all" mode - include both files and dirs)
+            if !is_all_mode {
+                if is_dir_mode && !entry.is_dir {
+                    return false;
+                }
+                if !is_dir_mode && entry.is_dir {
                     return false;
                 }
+            }
 
-                // Mode filter (skip for "all" mode - include both files and dirs)
-                if !is_all_mode {
-                    if is_dir_mode && !entry.is_dir {
-                        return false;
-                    }
-                    if !is_dir_mode && entry.is_dir {
+            // Binary extension filter for edit mode (skip for "all" mode)
+            if is_edit_mode {
+                if let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
+                    if self.config.binary_extensions.iter().any(|e| e == &ext.to_lowercase()) {
                         return false;
                     }
                 }
+            }
 
-                // Binary extension filter for edit mode (skip for "all" mode)
-                if is_edit_mode {
-                    if let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
-                        if BINARY_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
-                            return false;
-                        }
+            // Extension filter
+            if let Some(ref ext_filter) = req.extension {
+                if let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
+                    if ext.to_lowercase() != ext_filter.to_lowercase() {
+                        return false;
                     }
+                } else {
+                    return false;
                 }
+            }
 
-                // Extension filter
-                if let Some(ref ext_filter) = req.extension {
-                    if let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
-                        if ext.to_lowercase() != ext_filter.to_lowercase() {
-                            return false;
-                        }
-                    } else {
-                        return false;
+            // Extracted-attribute filters. A file that hasn't been through
+            // the extractor pool yet (or has no attributes at all) can't
+            // satisfy any of these.
+            if req.mime_prefix.is_some() || !req.attr_filters.is_empty() {
+                let Some(attrs) = self.attrs.get(&id) else { return false };
+
+                if let Some(ref prefix) = req.mime_prefix {
+                    match attrs.get("FILE_MIME") {
+                        Some(AttrValue::Text(mime)) if mime.starts_with(prefix.as_str()) => {}
+                        _ => return false,
                     }
                 }
 
-                // Verify actual substring match (trigrams can have false positives)
-                if !req.query.is_empty() {
-                    let path_lower = entry.path.to_lowercase();
-                    if !path_lower.contains(&query_lower) {
+                for filter in &req.attr_filters {
+                    let Some(value) = attrs.get(&filter.key) else { return false };
+                    if !attr_matches(&filter.op, value, &filter.value) {
                         return false;
                     }
                 }
+            }
 
-                true
+            true
+        };
+
+        let mut seen: HashSet<u32> = HashSet::new();
+        let mut scored: Vec<(SearchResult, i64)> = candidates
+            .into_iter()
+            .filter_map(|id| self.files.get(&id).map(|entry| (id, entry)))
+            .filter(|(id, entry)| matches_filters(*id, entry))
+            .filter(|(_, entry)| {
+                // Verify actual substring match (trigrams can have false positives)
+                req.query.is_empty() || entry.path.to_lowercase().contains(&query_lower)
             })
-            .map(|entry| SearchResult {
-                path: entry.path.clone(),
-                is_dir: entry.is_dir,
-                mtime: entry.mtime,
+            .map(|(id, entry)| {
+                seen.insert(id);
+                let score = score_match(&entry.path, &query_lower);
+                let error_string = self.health.get(&id).and_then(|h| h.error_string.clone());
+                (
+                    SearchResult { path: entry.path.clone(), is_dir: entry.is_dir, mtime: entry.mtime, error_string },
+                    score,
+                )
             })
             .collect();
 
-        // Sort by mtime descending (most recent first)
-        results.sort_by(|a, b| b.mtime.cmp(&a.mtime));
+        // Typo tolerance: if the exact-substring tier came up thin, widen the
+        // net to filenames within a small edit distance of the query, scored
+        // into a lower band so exact hits always rank above them.
+        if req.sort != "mtime" && scored.len() < TYPO_TIER_THRESHOLD && query_lower.chars().count() >= 3 {
+            for (id, entry) in self.files.iter() {
+                if seen.contains(id) || !matches_filters(*id, entry) {
+                    continue;
+                }
+                if let Some(score) = typo_score(&entry.path, &query_lower) {
+                    let error_string = self.health.get(id).and_then(|h| h.error_string.clone());
+                    scored.push((
+                        SearchResult { path: entry.path.clone(), is_dir: entry.is_dir, mtime: entry.mtime, error_string },
+                        score,
+                    ));
+                }
+            }
+        }
 
-        // Limit results
-        results.truncate(MAX_RESULTS);
+        if req.sort == "mtime" {
+            scored.sort_by(|a, b| b.0.mtime.cmp(&a.0.mtime));
+        } else {
+            scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.mtime.cmp(&a.0.mtime)));
+        }
 
-        results
+        scored.truncate(self.config.max_results);
+        scored.into_iter().map(|(r, _)| r).collect()
     }
 
     /// Search all bookmarks at once (much faster than multiple searches)
@@ -344,14 +940,11 @@ impl TrigramIndex {
             // Intersect posting lists for all trigrams
             let mut iter = trigrams.iter();
             let first = iter.next().unwrap();
-            let mut candidates = self.trigrams
-                .get(first)
-                .cloned()
-                .unwrap_or_default();
+            let mut candidates = self.posting_list(first).unwrap_or_default();
 
             for trigram in iter {
-                if let Some(set) = self.trigrams.get(trigram) {
-                    candidates = candidates.intersection(set).copied().collect();
+                if let Some(set) = self.posting_list(trigram) {
+                    candidates = candidates.intersection(&set).copied().collect();
                 } else {
                     return Vec::new();
                 }
@@ -362,48 +955,77 @@ impl TrigramIndex {
             candidates
         };
 
-        // Filter candidates and determine bookmark for each
-        let mut results: Vec<SearchAllResult> = candidates
-            .into_iter()
-            .filter_map(|id| self.files.get(&id))
-            .filter_map(|entry| {
-                // Find which bookmark this file belongs to
-                let bookmark_name = search_paths.iter()
-                    .find(|&bp| entry.path.starts_with(bp))
-                    .and_then(|bp| bookmark_map.get(bp).copied())?;
-
-                // Extension filter
-                if let Some(ref ext_filter) = req.extension {
-                    if let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
-                        if ext.to_lowercase() != ext_filter.to_lowercase() {
-                            return None;
-                        }
-                    } else {
-                        return None;
-                    }
+        // Extension filter plus bookmark resolution, shared by both the
+        // exact-substring pass and the typo-tolerance pass below.
+        let matches_filters = |entry: &FileEntry| -> Option<&str> {
+            let bookmark_name = search_paths.iter()
+                .find(|&bp| entry.path.starts_with(bp))
+                .and_then(|bp| bookmark_map.get(bp).copied())?;
+
+            if let Some(ref ext_filter) = req.extension {
+                match Path::new(&entry.path).extension().and_then(|e| e.to_str()) {
+                    Some(ext) if ext.to_lowercase() == ext_filter.to_lowercase() => {}
+                    _ => return None,
                 }
+            }
+
+            Some(bookmark_name)
+        };
+
+        let mut seen: HashSet<u32> = HashSet::new();
+        let mut scored: Vec<(SearchAllResult, i64)> = candidates
+            .into_iter()
+            .filter_map(|id| self.files.get(&id).map(|entry| (id, entry)))
+            .filter_map(|(id, entry)| {
+                let bookmark_name = matches_filters(entry)?;
 
                 // Verify actual substring match
-                if !req.query.is_empty() {
-                    let path_lower = entry.path.to_lowercase();
-                    if !path_lower.contains(&query_lower) {
-                        return None;
-                    }
+                if !req.query.is_empty() && !entry.path.to_lowercase().contains(&query_lower) {
+                    return None;
                 }
 
-                Some(SearchAllResult {
-                    path: entry.path.clone(),
-                    is_dir: entry.is_dir,
-                    mtime: entry.mtime,
-                    bookmark: bookmark_name.to_string(),
-                })
+                seen.insert(id);
+                let score = score_match(&entry.path, &query_lower);
+                Some((
+                    SearchAllResult {
+                        path: entry.path.clone(),
+                        is_dir: entry.is_dir,
+                        mtime: entry.mtime,
+                        bookmark: bookmark_name.to_string(),
+                    },
+                    score,
+                ))
             })
             .collect();
 
-        // Sort by mtime descending
-        results.sort_by(|a, b| b.mtime.cmp(&a.mtime));
-        results.truncate(MAX_RESULTS);
-        results
+        // Typo tolerance: same widen-the-net tier as `search` - if the
+        // exact-substring pass came up thin, also consider filenames within a
+        // small edit distance of the query, scored into a lower band so exact
+        // hits always rank first.
+        if scored.len() < TYPO_TIER_THRESHOLD && query_lower.chars().count() >= 3 {
+            for (id, entry) in self.files.iter() {
+                if seen.contains(id) {
+                    continue;
+                }
+                let Some(bookmark_name) = matches_filters(entry) else { continue };
+                if let Some(score) = typo_score(&entry.path, &query_lower) {
+                    scored.push((
+                        SearchAllResult {
+                            path: entry.path.clone(),
+                            is_dir: entry.is_dir,
+                            mtime: entry.mtime,
+                            bookmark: bookmark_name.to_string(),
+                        },
+                        score,
+                    ));
+                }
+            }
+        }
+
+        // Rank by match quality (score descending), with mtime as a tiebreaker.
+        scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.mtime.cmp(&a.0.mtime)));
+        scored.truncate(self.config.max_results);
+        scored.into_iter().map(|(r, _)| r).collect()
     }
 
     fn file_count(&self) -> usize {
@@ -412,64 +1034,371 @@ impl TrigramIndex {
 }
 
 // ============================================================================
-// Database Persistence (runs in dedicated thread)
+// Binary Snapshot (mmapped, zero-copy cold start)
 // ============================================================================
+//
+// `Database::load_into_index` used to rebuild every trigram posting list by
+// re-extracting trigrams from every path in the SQLite `files` table. That's
+// fine at thousands of files, but it dominates startup at multi-million-file
+// scale. Instead we keep a binary snapshot of the index next to the DB:
+// a fixed header, a sorted trigram directory pointing into a packed posting
+// region, and a packed file-entry region with paths stored in a side string
+// table. The snapshot is mmapped and the header/directory/postings are read
+// directly out of the mapping with no intermediate copy; a trigram is only
+// copied into the mutable `trigrams` HashMap (promoted) the first time a scan
+// or watcher event needs to mutate its posting list. SQLite remains the
+// write-ahead source of truth; the snapshot is regenerated atomically
+// (write to a temp file, then rename) after every full scan.
+
+const SNAPSHOT_MAGIC: [u8; 8] = *b"NIXNAVI\0";
+const SNAPSHOT_VERSION: u32 = 1;
+const SNAPSHOT_HEADER_LEN: usize = 36;
+
+/// Disambiguates the tmp path `write_snapshot` writes to, so two overlapping
+/// regenerations (e.g. a `RESCAN` racing the periodic network scan) never
+/// target the same tmp file even if they somehow aren't otherwise serialized.
+static SNAPSHOT_TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(BytesCast)]
+#[repr(C)]
+struct SnapshotHeader {
+    magic: [u8; 8],
+    version: U32Le,
+    file_count: U32Le,
+    trigram_count: U32Le,
+    trigram_dir_offset: U32Le,
+    postings_offset: U32Le,
+    files_offset: U32Le,
+    strings_offset: U32Le,
+}
 
-struct Database {
-    conn: Connection,
+#[derive(BytesCast, Clone, Copy)]
+#[repr(C)]
+struct TrigramDirEntry {
+    trigram: [u8; 3],
+    _pad: u8,
+    offset: U32Le,
+    len: U32Le,
 }
 
-impl Database {
-    fn open() -> rusqlite::Result<Self> {
-        let home = dirs::home_dir().expect("No home directory");
-        let db_path = home.join(DB_PATH);
+#[derive(BytesCast, Clone, Copy)]
+#[repr(C)]
+struct PackedFileEntry {
+    id: U32Le,
+    is_dir: u8,
+    _pad: [u8; 3],
+    mtime: U64Le,
+    size: U64Le,
+    path_offset: U32Le,
+    path_len: U32Le,
+}
 
-        if let Some(parent) = db_path.parent() {
-            fs::create_dir_all(parent).ok();
+/// A loaded, mmapped snapshot. Posting lists are decoded from `mmap` on demand;
+/// nothing here is copied until a query or mutation actually needs the bytes.
+struct IndexSnapshot {
+    mmap: Mmap,
+    trigram_dir_offset: usize,
+    postings_offset: usize,
+    files_offset: usize,
+    strings_offset: usize,
+}
+
+impl IndexSnapshot {
+    fn load(path: &Path) -> std::io::Result<Self> {
+        let file = fs::File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let (header, _) = SnapshotHeader::from_bytes(&mmap)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "truncated snapshot header"))?;
+        if header.magic != SNAPSHOT_MAGIC || header.version.get() != SNAPSHOT_VERSION {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "snapshot magic/version mismatch"));
         }
 
-        let conn = Connection::open(&db_path)?;
+        let snapshot = Self {
+            trigram_dir_offset: header.trigram_dir_offset.get() as usize,
+            postings_offset: header.postings_offset.get() as usize,
+            files_offset: header.files_offset.get() as usize,
+            strings_offset: header.strings_offset.get() as usize,
+            mmap,
+        };
 
-        conn.execute_batch(r#"
-            PRAGMA journal_mode = WAL;
-            PRAGMA synchronous = NORMAL;
-            PRAGMA cache_size = -64000;
-            PRAGMA temp_store = MEMORY;
+        // The header offsets come straight off disk; a truncated or
+        // otherwise corrupted snapshot file must not panic the daemon on
+        // every cold start (a bootloop), so validate them once up front and
+        // let every later slicing method trust them.
+        if snapshot.trigram_dir_offset > snapshot.postings_offset
+            || snapshot.postings_offset > snapshot.files_offset
+            || snapshot.files_offset > snapshot.strings_offset
+            || snapshot.strings_offset > snapshot.mmap.len()
+        {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "snapshot region offsets out of bounds"));
+        }
+        if snapshot.trigram_dir().is_none() || snapshot.file_entries().is_none() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "snapshot region length misaligned"));
+        }
 
-            CREATE TABLE IF NOT EXISTS files (
-                id INTEGER PRIMARY KEY,
-                path TEXT UNIQUE NOT NULL,
-                is_dir INTEGER NOT NULL,
-                mtime INTEGER NOT NULL,
-                size INTEGER NOT NULL
-            );
+        Ok(snapshot)
+    }
 
-            CREATE TABLE IF NOT EXISTS bookmarks (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                path TEXT UNIQUE NOT NULL,
-                is_network INTEGER NOT NULL,
-                last_scan INTEGER
-            );
+    fn trigram_dir(&self) -> Option<&[TrigramDirEntry]> {
+        let bytes = &self.mmap[self.trigram_dir_offset..self.postings_offset];
+        let count = bytes.len() / std::mem::size_of::<TrigramDirEntry>();
+        let (entries, _) = TrigramDirEntry::slice_from_bytes(bytes, count).ok()?;
+        Some(entries)
+    }
 
-            CREATE INDEX IF NOT EXISTS idx_files_path ON files(path);
-            CREATE INDEX IF NOT EXISTS idx_files_mtime ON files(mtime);
-        "#)?;
+    /// Binary search the sorted trigram directory and decode the matching
+    /// posting list straight out of the mmap.
+    fn decode_posting_list(&self, trigram: &[u8; 3]) -> Option<HashSet<u32>> {
+        let dir = self.trigram_dir()?;
+        let idx = dir.binary_search_by_key(trigram, |e| e.trigram).ok()?;
+        let entry = dir[idx];
+        let start = self.postings_offset + entry.offset.get() as usize;
+        let len = entry.len.get() as usize;
+        let end = start.checked_add(len.checked_mul(4)?)?;
+        let bytes = self.mmap.get(start..end)?;
+        let (ids, _) = U32Le::slice_from_bytes(bytes, len).ok()?;
+        Some(ids.iter().map(|id| id.get()).collect())
+    }
 
-        Ok(Self { conn })
+    fn file_entries(&self) -> Option<&[PackedFileEntry]> {
+        let bytes = &self.mmap[self.files_offset..self.strings_offset];
+        let count = bytes.len() / std::mem::size_of::<PackedFileEntry>();
+        let (entries, _) = PackedFileEntry::slice_from_bytes(bytes, count).ok()?;
+        Some(entries)
     }
 
-    fn load_into_index(&self, index: &mut TrigramIndex) -> rusqlite::Result<usize> {
-        let mut stmt = self.conn.prepare("SELECT id, path, is_dir, mtime, size FROM files")?;
-        let mut count = 0;
+    fn path_for(&self, entry: &PackedFileEntry) -> Option<&str> {
+        let start = self.strings_offset.checked_add(entry.path_offset.get() as usize)?;
+        let end = start.checked_add(entry.path_len.get() as usize)?;
+        std::str::from_utf8(self.mmap.get(start..end)?).ok()
+    }
+}
 
-        let rows = stmt.query_map([], |row| {
-            Ok(FileEntry {
-                id: row.get(0)?,
-                path: row.get(1)?,
+fn snapshot_file_path() -> PathBuf {
+    let home = dirs::home_dir().expect("No home directory");
+    home.join(SNAPSHOT_PATH)
+}
+
+/// Serialize the current index to the binary snapshot format and install it
+/// atomically. Posting lists still living only in the mmapped snapshot (never
+/// promoted) are carried over alongside the promoted ones.
+fn write_snapshot(index: &TrigramIndex) -> std::io::Result<()> {
+    let path = snapshot_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut trigrams: Vec<([u8; 3], HashSet<u32>)> =
+        index.trigrams.iter().map(|(t, ids)| (*t, ids.clone())).collect();
+    if let Some(dir) = index.snapshot.as_ref().and_then(|s| s.trigram_dir().map(|d| (s, d))) {
+        let (snapshot, entries) = dir;
+        for e in entries.iter().filter(|e| !index.trigrams.contains_key(&e.trigram)) {
+            if let Some(ids) = snapshot.decode_posting_list(&e.trigram) {
+                trigrams.push((e.trigram, ids));
+            }
+        }
+    }
+    trigrams.sort_by_key(|(t, _)| *t);
+
+    let mut postings = Vec::new();
+    let mut trigram_dir = Vec::new();
+    for (trigram, ids) in &trigrams {
+        let offset = postings.len() as u32;
+        let mut sorted_ids: Vec<u32> = ids.iter().copied().collect();
+        sorted_ids.sort_unstable();
+        for id in &sorted_ids {
+            postings.extend_from_slice(&id.to_le_bytes());
+        }
+        trigram_dir.extend_from_slice(trigram.as_slice());
+        trigram_dir.push(0);
+        trigram_dir.extend_from_slice(&offset.to_le_bytes());
+        trigram_dir.extend_from_slice(&(sorted_ids.len() as u32).to_le_bytes());
+    }
+
+    let mut files_region = Vec::new();
+    let mut strings_region: Vec<u8> = Vec::new();
+    let mut files: Vec<&FileEntry> = index.files.values().collect();
+    files.sort_by_key(|f| f.id);
+    for f in &files {
+        let path_offset = strings_region.len() as u32;
+        strings_region.extend_from_slice(f.path.as_bytes());
+
+        files_region.extend_from_slice(&f.id.to_le_bytes());
+        files_region.push(f.is_dir as u8);
+        files_region.extend_from_slice(&[0u8; 3]);
+        files_region.extend_from_slice(&(f.mtime as u64).to_le_bytes());
+        files_region.extend_from_slice(&f.size.to_le_bytes());
+        files_region.extend_from_slice(&path_offset.to_le_bytes());
+        files_region.extend_from_slice(&(f.path.len() as u32).to_le_bytes());
+    }
+
+    let trigram_dir_offset = SNAPSHOT_HEADER_LEN as u32;
+    let postings_offset = trigram_dir_offset + trigram_dir.len() as u32;
+    let files_offset = postings_offset + postings.len() as u32;
+    let strings_offset = files_offset + files_region.len() as u32;
+
+    let mut out = Vec::with_capacity(strings_offset as usize + strings_region.len());
+    out.extend_from_slice(&SNAPSHOT_MAGIC);
+    out.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(files.len() as u32).to_le_bytes());
+    out.extend_from_slice(&(trigrams.len() as u32).to_le_bytes());
+    out.extend_from_slice(&trigram_dir_offset.to_le_bytes());
+    out.extend_from_slice(&postings_offset.to_le_bytes());
+    out.extend_from_slice(&files_offset.to_le_bytes());
+    out.extend_from_slice(&strings_offset.to_le_bytes());
+    out.extend_from_slice(&trigram_dir);
+    out.extend_from_slice(&postings);
+    out.extend_from_slice(&files_region);
+    out.extend_from_slice(&strings_region);
+
+    let tmp_path = path.with_extension(format!(
+        "snapshot.tmp.{}.{}",
+        std::process::id(),
+        SNAPSHOT_TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+    ));
+    fs::write(&tmp_path, &out)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Load the mmapped snapshot (if one exists and parses cleanly) and eagerly
+/// populate `files`/`path_to_id` from it — cheap, since unlike trigram
+/// reconstruction it's just fixed-size record decoding, no string scanning.
+/// Trigram posting lists stay in the mmap until `promote` pulls one out.
+fn load_snapshot(index: &mut TrigramIndex) -> Option<usize> {
+    let snapshot = IndexSnapshot::load(&snapshot_file_path()).ok()?;
+
+    let mut count = 0;
+    for packed in snapshot.file_entries()? {
+        let id = packed.id.get();
+        let path_str = snapshot.path_for(packed)?.to_string();
+        if id >= index.next_id {
+            index.next_id = id + 1;
+        }
+        let entry = FileEntry {
+            id,
+            path: path_str.clone(),
+            is_dir: packed.is_dir != 0,
+            mtime: packed.mtime.get() as i64,
+            size: packed.size.get(),
+            // Not part of the snapshot format; rebuilt lazily on the next
+            // scan/watch event that touches this path.
+            fingerprint: None,
+        };
+        index.path_to_id.insert(path_str, id);
+        index.files.insert(id, entry);
+        count += 1;
+    }
+
+    index.snapshot = Some(snapshot);
+    Some(count)
+}
+
+/// Regenerate the on-disk snapshot from the current in-memory index. Called
+/// after full scans so the next cold start can mmap instead of rebuilding.
+///
+/// Takes only a read lock: concurrent regenerations are already made safe by
+/// `write_snapshot`'s per-call unique tmp path (see `SNAPSHOT_TMP_COUNTER`),
+/// so holding the write lock here would needlessly stall `SEARCH`/`SEARCH_ALL`
+/// for the duration of a full snapshot write.
+async fn regenerate_snapshot(index: &Arc<AsyncRwLock<TrigramIndex>>) {
+    let idx = index.read().await;
+    if let Err(e) = write_snapshot(&idx) {
+        warn!("Failed to write index snapshot: {}", e);
+    }
+}
+
+// ============================================================================
+// Database Persistence (runs as a dedicated Tokio task)
+// ============================================================================
+
+struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    fn open(config: &Config) -> rusqlite::Result<Self> {
+        let home = dirs::home_dir().expect("No home directory");
+        let db_path = home.join(&config.db_path);
+
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+
+        let conn = Connection::open(&db_path)?;
+
+        conn.execute_batch(r#"
+            PRAGMA journal_mode = WAL;
+            PRAGMA synchronous = NORMAL;
+            PRAGMA cache_size = -64000;
+            PRAGMA temp_store = MEMORY;
+
+            CREATE TABLE IF NOT EXISTS files (
+                id INTEGER PRIMARY KEY,
+                path TEXT UNIQUE NOT NULL,
+                is_dir INTEGER NOT NULL,
+                mtime INTEGER NOT NULL,
+                size INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS bookmarks (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                path TEXT UNIQUE NOT NULL,
+                is_network INTEGER NOT NULL,
+                last_scan INTEGER
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_files_path ON files(path);
+            CREATE INDEX IF NOT EXISTS idx_files_mtime ON files(mtime);
+
+            CREATE TABLE IF NOT EXISTS file_hashes (
+                path TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                hash TEXT NOT NULL,
+                PRIMARY KEY (path, mtime, size)
+            );
+
+            CREATE TABLE IF NOT EXISTS file_attributes (
+                path TEXT PRIMARY KEY,
+                attrs_json TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY,
+                kind TEXT NOT NULL,
+                status_json TEXT NOT NULL,
+                started_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS file_health (
+                path TEXT PRIMARY KEY,
+                type_of_file TEXT NOT NULL,
+                error_string TEXT,
+                checked_size INTEGER NOT NULL,
+                checked_mtime INTEGER NOT NULL
+            );
+        "#)?;
+
+        Ok(Self { conn })
+    }
+
+    fn load_into_index(&self, index: &mut TrigramIndex) -> rusqlite::Result<usize> {
+        let mut stmt = self.conn.prepare("SELECT id, path, is_dir, mtime, size FROM files")?;
+        let mut count = 0;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(FileEntry {
+                id: row.get(0)?,
+                path: row.get(1)?,
                 is_dir: row.get::<_, i32>(2)? != 0,
                 mtime: row.get(3)?,
                 size: row.get(4)?,
+                // Like the snapshot path, rebuilt lazily rather than persisted
+                // in the `files` table.
+                fingerprint: None,
             })
         })?;
 
@@ -500,6 +1429,14 @@ impl Database {
             count += 1;
         }
 
+        self.load_bookmarks_into(index)?;
+
+        Ok(count)
+    }
+
+    /// Load just the `bookmarks` table. Used on its own when the file index
+    /// was restored from the mmapped snapshot instead of SQLite.
+    fn load_bookmarks_into(&self, index: &mut TrigramIndex) -> rusqlite::Result<()> {
         let mut stmt = self.conn.prepare("SELECT name, path, is_network FROM bookmarks")?;
         let bookmarks = stmt.query_map([], |row| {
             Ok(Bookmark {
@@ -513,7 +1450,7 @@ impl Database {
             index.bookmarks.push(bookmark?);
         }
 
-        Ok(count)
+        Ok(())
     }
 
     fn save_file(&self, entry: &FileEntry) {
@@ -525,136 +1462,875 @@ impl Database {
         }
     }
 
-    fn remove_file(&self, path: &str) {
-        if let Err(e) = self.conn.execute("DELETE FROM files WHERE path = ?1", params![path]) {
-            warn!("Failed to remove file: {}", e);
-        }
-    }
+    fn remove_file(&self, path: &str) {
+        if let Err(e) = self.conn.execute("DELETE FROM files WHERE path = ?1", params![path]) {
+            warn!("Failed to remove file: {}", e);
+        }
+    }
+
+    fn save_bookmark(&self, bookmark: &Bookmark) {
+        if let Err(e) = self.conn.execute(
+            "INSERT OR REPLACE INTO bookmarks (name, path, is_network) VALUES (?1, ?2, ?3)",
+            params![bookmark.name, bookmark.path, bookmark.is_network as i32],
+        ) {
+            warn!("Failed to save bookmark: {}", e);
+        }
+    }
+
+    fn clear_files_under(&self, path: &str) {
+        if let Err(e) = self.conn.execute(
+            "DELETE FROM files WHERE path LIKE ?1",
+            params![format!("{}%", path)],
+        ) {
+            warn!("Failed to clear files: {}", e);
+        }
+    }
+
+    fn save_hash(&self, path: &str, mtime: i64, size: u64, hash: &str) {
+        if let Err(e) = self.conn.execute(
+            "INSERT OR REPLACE INTO file_hashes (path, mtime, size, hash) VALUES (?1, ?2, ?3, ?4)",
+            params![path, mtime, size, hash],
+        ) {
+            warn!("Failed to save hash: {}", e);
+        }
+    }
+
+    /// Look up a cached hash, valid only if `mtime`/`size` still match what we hashed.
+    fn get_cached_hash(&self, path: &str, mtime: i64, size: u64) -> Option<String> {
+        self.conn
+            .query_row(
+                "SELECT hash FROM file_hashes WHERE path = ?1 AND mtime = ?2 AND size = ?3",
+                params![path, mtime, size],
+                |row| row.get(0),
+            )
+            .ok()
+    }
+
+    fn save_attributes(&self, path: &str, attrs: &HashMap<String, AttrValue>) {
+        let attrs_json = match serde_json::to_string(attrs) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize attributes for {}: {}", path, e);
+                return;
+            }
+        };
+        if let Err(e) = self.conn.execute(
+            "INSERT OR REPLACE INTO file_attributes (path, attrs_json) VALUES (?1, ?2)",
+            params![path, attrs_json],
+        ) {
+            warn!("Failed to save attributes: {}", e);
+        }
+    }
+
+    /// Load the `file_attributes` table, keyed back onto file IDs via
+    /// `index.path_to_id`. Rows whose path is no longer in the index (the
+    /// file was removed since extraction) are silently dropped.
+    fn load_attributes_into(&self, index: &mut TrigramIndex) -> rusqlite::Result<usize> {
+        let mut stmt = self.conn.prepare("SELECT path, attrs_json FROM file_attributes")?;
+        let rows = stmt.query_map([], |row| {
+            let path: String = row.get(0)?;
+            let attrs_json: String = row.get(1)?;
+            Ok((path, attrs_json))
+        })?;
+
+        let mut count = 0;
+        for row in rows {
+            let (path, attrs_json) = row?;
+            let Some(&id) = index.path_to_id.get(&path) else { continue };
+            let Ok(attrs) = serde_json::from_str::<HashMap<String, AttrValue>>(&attrs_json) else { continue };
+            index.attrs.insert(id, attrs);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Persist a whole scan batch in one transaction instead of one implicit
+    /// transaction per row, which is the dominant cost on large trees.
+    fn save_batch(&self, entries: &[FileEntry]) {
+        let tx = match self.conn.unchecked_transaction() {
+            Ok(tx) => tx,
+            Err(e) => {
+                warn!("Failed to start batch transaction: {}", e);
+                return;
+            }
+        };
+
+        for entry in entries {
+            if let Err(e) = tx.execute(
+                "INSERT OR REPLACE INTO files (id, path, is_dir, mtime, size) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entry.id, entry.path, entry.is_dir as i32, entry.mtime, entry.size],
+            ) {
+                warn!("Failed to save file in batch: {}", e);
+            }
+        }
+
+        if let Err(e) = tx.commit() {
+            warn!("Failed to commit batch: {}", e);
+        }
+    }
+
+    /// Cap on persisted task rows so `tasks` doesn't grow forever across
+    /// daemon restarts; old rows are pruned opportunistically on every save.
+    const MAX_PERSISTED_TASKS: i64 = 500;
+
+    fn save_task(&self, report: &TaskReport) {
+        let status_json = match serde_json::to_string(&report.status) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize task {} status: {}", report.id, e);
+                return;
+            }
+        };
+        if let Err(e) = self.conn.execute(
+            "INSERT OR REPLACE INTO tasks (id, kind, status_json, started_at) VALUES (?1, ?2, ?3, ?4)",
+            params![report.id, report.kind, status_json, report.started_at],
+        ) {
+            warn!("Failed to save task: {}", e);
+        }
+        if let Err(e) = self.conn.execute(
+            "DELETE FROM tasks WHERE id <= (SELECT COALESCE(MAX(id), 0) FROM tasks) - ?1",
+            params![Self::MAX_PERSISTED_TASKS],
+        ) {
+            warn!("Failed to prune old tasks: {}", e);
+        }
+    }
+
+    /// Load persisted task history for `TaskStore::load_from`. A task still
+    /// `Enqueued`/`Processing` when it was saved means the daemon went down
+    /// mid-run, so it's reported as `Failed` instead of looking like it's
+    /// still in progress.
+    fn load_tasks(&self) -> rusqlite::Result<Vec<TaskReport>> {
+        let mut stmt = self.conn.prepare("SELECT id, kind, status_json, started_at FROM tasks")?;
+        let rows = stmt.query_map([], |row| {
+            let id: u64 = row.get(0)?;
+            let kind: String = row.get(1)?;
+            let status_json: String = row.get(2)?;
+            let started_at: i64 = row.get(3)?;
+            Ok((id, kind, status_json, started_at))
+        })?;
+
+        let mut reports = Vec::new();
+        for row in rows {
+            let (id, kind, status_json, started_at) = row?;
+            let status = match serde_json::from_str::<TaskStatus>(&status_json) {
+                Ok(TaskStatus::Enqueued) | Ok(TaskStatus::Processing { .. }) => {
+                    TaskStatus::Failed { msg: "interrupted by daemon restart".to_string() }
+                }
+                Ok(status) => status,
+                Err(_) => continue,
+            };
+            reports.push(TaskReport { id, kind, status, started_at });
+        }
+        Ok(reports)
+    }
+
+    fn save_file_health(&self, path: &str, health: &FileHealth) {
+        if let Err(e) = self.conn.execute(
+            "INSERT OR REPLACE INTO file_health (path, type_of_file, error_string, checked_size, checked_mtime) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![path, health.type_of_file, health.error_string, health.checked_size, health.checked_mtime],
+        ) {
+            warn!("Failed to save file health: {}", e);
+        }
+    }
+
+    /// Load the `file_health` table, keyed back onto file IDs via
+    /// `index.path_to_id`. Rows whose path is no longer in the index are
+    /// silently dropped, same as `load_attributes_into`.
+    fn load_file_health_into(&self, index: &mut TrigramIndex) -> rusqlite::Result<usize> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, type_of_file, error_string, checked_size, checked_mtime FROM file_health",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let path: String = row.get(0)?;
+            let health = FileHealth {
+                type_of_file: row.get(1)?,
+                error_string: row.get(2)?,
+                checked_size: row.get(3)?,
+                checked_mtime: row.get(4)?,
+            };
+            Ok((path, health))
+        })?;
+
+        let mut count = 0;
+        for row in rows {
+            let (path, health) = row?;
+            let Some(&id) = index.path_to_id.get(&path) else { continue };
+            index.health.insert(id, health);
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn process_op(&self, op: DbOp) {
+        match op {
+            DbOp::SaveFile(entry) => self.save_file(&entry),
+            DbOp::RemoveFile(path) => self.remove_file(&path),
+            DbOp::SaveBookmark(bookmark) => self.save_bookmark(&bookmark),
+            DbOp::ClearFilesUnder(path) => self.clear_files_under(&path),
+            DbOp::SaveHash { path, mtime, size, hash } => self.save_hash(&path, mtime, size, &hash),
+            DbOp::SaveBatch(entries) => self.save_batch(&entries),
+            DbOp::SaveAttributes { path, attrs } => self.save_attributes(&path, &attrs),
+            DbOp::SaveTask(report) => self.save_task(&report),
+            DbOp::SaveFileHealth { path, health } => self.save_file_health(&path, &health),
+        }
+    }
+}
+
+/// Start the database task that serializes writes from an mpsc channel. Runs
+/// on the Tokio runtime rather than a dedicated OS thread; `process_op` is
+/// plain synchronous `rusqlite` work, but each call is quick enough that it
+/// doesn't need its own `spawn_blocking` round-trip.
+fn start_db_thread(db: Database) -> mpsc::UnboundedSender<DbOp> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<DbOp>();
+
+    tokio::spawn(async move {
+        while let Some(op) = rx.recv().await {
+            db.process_op(op);
+        }
+    });
+
+    tx
+}
+
+// ============================================================================
+// File Scanner
+// ============================================================================
+
+fn is_network_mount(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+
+    if path_str.starts_with("/mnt/") ||
+       path_str.starts_with("/media/") ||
+       path_str.starts_with("/net/") {
+        if let Ok(mounts) = fs::read_to_string("/proc/mounts") {
+            for line in mounts.lines() {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() >= 3 {
+                    let mount_point = parts[1];
+                    let fs_type = parts[2];
+
+                    if path_str.starts_with(mount_point) {
+                        return matches!(fs_type, "nfs" | "nfs4" | "cifs" | "smb" | "smbfs" | "fuse.sshfs");
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+fn should_exclude(path: &Path, config: &Config) -> bool {
+    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+        // Exact match or prefix match for .Trash-* folders
+        config
+            .exclude_patterns
+            .iter()
+            .any(|p| name == p.as_str() || (p == ".Trash" && name.starts_with(".Trash")))
+    } else {
+        false
+    }
+}
+
+fn get_mtime(path: &Path) -> i64 {
+    path.metadata()
+        .and_then(|m| m.modified())
+        .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Rows per `DbOp::SaveBatch` transaction. Large enough to amortize the
+/// per-transaction fsync cost, small enough to keep memory bounded on huge trees.
+const SCAN_BATCH_SIZE: usize = 4000;
+
+/// Outcome of the blocking walk+metadata stage of a scan. `Cancelled` carries
+/// how many entries had been walked (when cancelled mid-walk) or how many
+/// changed entries had been collected (when cancelled before persisting);
+/// none of them are indexed or persisted. `Done.total` is every
+/// entry walked; `Done.changed` is just the ones whose `(mtime, size)` differ
+/// from `known` (or are new), which is all `scan_directory` needs to re-index
+/// and persist.
+enum ScanWalkResult {
+    Cancelled(usize),
+    Done { total: usize, changed: Vec<FileEntry> },
+}
+
+/// Walk `root` and gather mtime/size/fingerprint metadata in parallel via
+/// rayon, honoring job pause/cancel signals along the way. Pure CPU/IO work
+/// with no index or channel access (`known` is a plain snapshot, not a live
+/// reference), so `scan_directory` runs it on a blocking-pool thread instead
+/// of the async executor.
+///
+/// `known` maps path -> `(mtime, size, has_fingerprint)` as currently
+/// recorded in the index, so a file whose metadata hasn't moved since the
+/// last scan can skip both the (file-reading) fingerprint
+/// computation and the eventual re-index/DB write - the same `(mtime, size)`
+/// bookkeeping UpEnd's FsStore uses to avoid rehashing unchanged files.
+/// `has_fingerprint` guards against skipping entries that were loaded from
+/// the snapshot without one: the snapshot format doesn't persist
+/// fingerprints, so a file that's never seen a real scan since its last
+/// cold start would otherwise sit with `fingerprint: None` forever and
+/// silently drop out of move/rename detection.
+fn walk_and_collect(
+    root: &Path,
+    config: &Config,
+    job: Option<&ScanJob>,
+    known: &HashMap<String, (i64, u64, bool)>,
+) -> ScanWalkResult {
+    // `file_type()` on a WalkDir entry comes straight from the readdir dirent on
+    // Linux, so collecting the walk itself is cheap; the expensive part is the
+    // mtime/size `metadata()` call, which we do once per entry (and skip size
+    // entirely for directories) and fan out across a rayon thread pool.
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !should_exclude(e.path(), config));
+
+    let mut entries: Vec<(String, bool)> = Vec::new();
+    for entry in walker.filter_map(|e| e.ok()) {
+        if let Some(job) = job {
+            job.wait_if_paused();
+            if job.is_cancelled() {
+                info!("Scan of {} cancelled during walk", root.display());
+                return ScanWalkResult::Cancelled(entries.len());
+            }
+            job.tick(entries.len());
+        }
+        entries.push((entry.path().to_string_lossy().to_string(), entry.file_type().is_dir()));
+    }
+
+    if let Some(job) = job {
+        job.set_totals(entries.len());
+        job.set_stage(JobStage::Indexing);
+    }
+
+    let total = entries.len();
+
+    let changed: Vec<FileEntry> = entries
+        .into_par_iter()
+        .filter_map(|(path_str, is_dir)| {
+            let meta = Path::new(&path_str).metadata().ok()?;
+            let mtime = meta
+                .modified()
+                .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64)
+                .unwrap_or(0);
+            let size = if is_dir { 0 } else { meta.len() };
+
+            // Directories never carry a fingerprint, so only files need one
+            // recorded before they can be skipped.
+            if known.get(&path_str) == Some(&(mtime, size, !is_dir)) {
+                return None;
+            }
+
+            let fingerprint = if is_dir { None } else { compute_fingerprint(Path::new(&path_str), size) };
+            Some(FileEntry { id: 0, path: path_str, is_dir, mtime, size, fingerprint })
+        })
+        .collect();
+
+    if let Some(job) = job {
+        if job.is_cancelled() {
+            info!("Scan of {} cancelled before persisting", root.display());
+            return ScanWalkResult::Cancelled(changed.len());
+        }
+        job.set_stage(JobStage::Persisting);
+    }
+
+    ScanWalkResult::Done { total, changed }
+}
+
+async fn scan_directory(
+    root: &Path,
+    index: &Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: &mpsc::UnboundedSender<DbOp>,
+    config: &Config,
+    job: Option<&ScanJob>,
+    extractor: Option<&ExtractorPool>,
+) -> usize {
+    let start = std::time::Instant::now();
+
+    info!("Scanning directory: {}", root.display());
+
+    if let Some(job) = job {
+        job.set_stage(JobStage::Walking);
+    }
+
+    let known: HashMap<String, (i64, u64, bool)> = {
+        let idx = index.read().await;
+        idx.files
+            .values()
+            .map(|f| (f.path.clone(), (f.mtime, f.size, f.fingerprint.is_some())))
+            .collect()
+    };
+
+    let root_buf = root.to_path_buf();
+    let config_owned = config.clone();
+    let job_owned = job.cloned();
+    let walk_result = tokio::task::spawn_blocking(move || {
+        walk_and_collect(&root_buf, &config_owned, job_owned.as_ref(), &known)
+    })
+    .await
+    .unwrap_or(ScanWalkResult::Done { total: 0, changed: Vec::new() });
+
+    let (total, scanned) = match walk_result {
+        ScanWalkResult::Cancelled(count) => return count,
+        ScanWalkResult::Done { total, changed } => (total, changed),
+    };
+
+    // Merge into the index under a single write lock instead of one lock
+    // acquisition per file.
+    let scanned: Vec<FileEntry> = {
+        let mut idx = index.write().await;
+        scanned
+            .into_iter()
+            .map(|mut entry| {
+                entry.id = idx.add(entry.path.clone(), entry.is_dir, entry.mtime, entry.size, entry.fingerprint);
+                entry
+            })
+            .collect()
+    };
+
+    if let Some(extractor) = extractor {
+        for entry in &scanned {
+            if !entry.is_dir {
+                extractor.submit(entry.id, entry.path.clone());
+            }
+        }
+    }
+
+    let changed_count = scanned.len();
+    for chunk in scanned.chunks(SCAN_BATCH_SIZE) {
+        let _ = db_tx.send(DbOp::SaveBatch(chunk.to_vec()));
+    }
+
+    if let Some(job) = job {
+        job.tick(total);
+    }
+
+    let elapsed = start.elapsed();
+    info!("Scanned {} files ({} changed) in {:?}", total, changed_count, elapsed);
+
+    total
+}
+
+// ============================================================================
+// Duplicate Detection
+// ============================================================================
+
+const HASH_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Bytes read from each end of a file for `compute_fingerprint`.
+const FINGERPRINT_SAMPLE_SIZE: u64 = 64 * 1024;
+
+/// Cheap, scan-time-affordable stand-in for a full content hash: combines the
+/// file size with an xxhash of the first and last `FINGERPRINT_SAMPLE_SIZE`
+/// bytes. Cheap enough to run on every scanned file (unlike `hash_file`,
+/// which reads the whole file and is only run lazily for confirmed
+/// same-size candidates), good enough to notice "this is probably the same
+/// file that used to live somewhere else" for move detection.
+fn compute_fingerprint(path: &Path, size: u64) -> Option<u64> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Xxh3::new();
+    hasher.update(&size.to_le_bytes());
+
+    let mut head = vec![0u8; FINGERPRINT_SAMPLE_SIZE.min(size) as usize];
+    file.read_exact(&mut head).ok()?;
+    hasher.update(&head);
+
+    if size > FINGERPRINT_SAMPLE_SIZE {
+        let tail_len = FINGERPRINT_SAMPLE_SIZE.min(size);
+        file.seek(std::io::SeekFrom::End(-(tail_len as i64))).ok()?;
+        let mut tail = vec![0u8; tail_len as usize];
+        file.read_exact(&mut tail).ok()?;
+        hasher.update(&tail);
+    }
+
+    Some(hasher.digest())
+}
+
+/// Cap on `TrigramIndex::pending_removals` so a directory tree full of
+/// genuine deletes (never followed by a matching create) can't grow it
+/// without bound. Evicting arbitrarily is fine: worst case is a missed move
+/// detection, not an incorrect one.
+const MAX_PENDING_REMOVALS: usize = 10_000;
+
+fn prune_pending_removals(pending: &mut HashMap<u64, String>) {
+    if pending.len() >= MAX_PENDING_REMOVALS {
+        pending.clear();
+    }
+}
+
+/// Hash a file's contents in fixed-size chunks so memory use stays bounded
+/// regardless of file size.
+fn hash_file(path: &Path, hasher: Hasher) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut buf = [0u8; HASH_CHUNK_SIZE];
+
+    match hasher {
+        Hasher::Xxh3 => {
+            let mut h = Xxh3::new();
+            loop {
+                let n = file.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            Ok(format!("{:016x}", h.digest()))
+        }
+        Hasher::Blake3 => {
+            let mut h = blake3::Hasher::new();
+            loop {
+                let n = file.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            Ok(h.finalize().to_hex().to_string())
+        }
+    }
+}
+
+/// Find groups of files with identical content under the given bookmark paths.
+///
+/// Two-stage approach: bucket by size first (files with a unique size can't have
+/// a duplicate and are skipped for free), then hash only the files inside buckets
+/// that have more than one candidate. Hard-linked files (same `(st_dev, st_ino)`)
+/// are treated as a single file, not a duplicate pair, since they're already the
+/// same data on disk.
+async fn find_duplicates(
+    index: &Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: &mpsc::UnboundedSender<DbOp>,
+    req: &FindDuplicatesRequest,
+    config: &Config,
+) -> Vec<DuplicateGroup> {
+    let by_size: HashMap<u64, Vec<FileEntry>> = {
+        let idx = index.read().await;
+        let mut map: HashMap<u64, Vec<FileEntry>> = HashMap::new();
+        for entry in idx.files.values() {
+            if entry.is_dir || entry.size == 0 {
+                continue;
+            }
+            if !req.bookmark_paths.is_empty()
+                && !req.bookmark_paths.iter().any(|bp| entry.path.starts_with(bp.as_str()))
+            {
+                continue;
+            }
+            map.entry(entry.size).or_default().push(entry.clone());
+        }
+        map
+    };
+
+    // The actual re-stat-and-hash pass is blocking file IO, so it runs on a
+    // blocking-pool thread rather than the async executor.
+    let db_tx = db_tx.clone();
+    let req = req.clone();
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || {
+        // Re-stat files right before hashing since the index may be stale, and
+        // dedupe hard links so they don't show up as "duplicates" of themselves.
+        let cache_db = Database::open(&config).ok();
+        let mut groups = Vec::new();
+
+        for (size, entries) in by_size {
+            if entries.len() < 2 {
+                continue;
+            }
+
+            let mut seen_inodes: HashSet<(u64, u64)> = HashSet::new();
+            let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+
+            for entry in entries {
+                let path = Path::new(&entry.path);
+                let meta = match path.metadata() {
+                    Ok(m) => m,
+                    Err(_) => continue, // vanished since the index was built
+                };
+                if meta.len() != size {
+                    continue; // stale index entry
+                }
+                if !seen_inodes.insert((meta.dev(), meta.ino())) {
+                    continue; // hard link to a file we've already counted
+                }
+
+                let mtime = get_mtime(path);
+                let cached = cache_db
+                    .as_ref()
+                    .and_then(|db| db.get_cached_hash(&entry.path, mtime, size));
+
+                let hash = match cached {
+                    Some(h) => h,
+                    None => match hash_file(path, req.hasher) {
+                        Ok(h) => {
+                            let _ = db_tx.send(DbOp::SaveHash {
+                                path: entry.path.clone(),
+                                mtime,
+                                size,
+                                hash: h.clone(),
+                            });
+                            h
+                        }
+                        Err(e) => {
+                            warn!("Failed to hash {}: {}", entry.path, e);
+                            continue;
+                        }
+                    },
+                };
+
+                by_hash.entry(hash).or_default().push(entry.path.clone());
+            }
 
-    fn save_bookmark(&self, bookmark: &Bookmark) {
-        if let Err(e) = self.conn.execute(
-            "INSERT OR REPLACE INTO bookmarks (name, path, is_network) VALUES (?1, ?2, ?3)",
-            params![bookmark.name, bookmark.path, bookmark.is_network as i32],
-        ) {
-            warn!("Failed to save bookmark: {}", e);
+            for (_, paths) in by_hash {
+                if paths.len() > 1 {
+                    groups.push(DuplicateGroup { size, paths });
+                }
+            }
         }
-    }
 
-    fn clear_files_under(&self, path: &str) {
-        if let Err(e) = self.conn.execute(
-            "DELETE FROM files WHERE path LIKE ?1",
-            params![format!("{}%", path)],
-        ) {
-            warn!("Failed to clear files: {}", e);
-        }
+        groups
+    })
+    .await
+    .unwrap_or_default()
+}
+
+// ============================================================================
+// Metadata Extractors
+// ============================================================================
+//
+// Pulls typed attributes (MIME type, image EXIF fields, audio tags, PDF page
+// count) out of file contents. This is strictly slower than the mtime/size
+// pass in `scan_directory` above - reading tag blocks, decoding EXIF IFDs,
+// walking a PDF's xref table - so it never runs on the scan's own thread.
+// Every non-directory file discovered by a scan is instead handed to a small
+// bounded `ExtractorPool`, and results land in `TrigramIndex::attrs` (and the
+// `file_attributes` table) whenever extraction happens to finish.
+
+/// Extensions `infer`'s content sniffing doesn't cover, since it only looks
+/// at magic bytes and most of these are plain text.
+const MIME_EXT_FALLBACK: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("json", "application/json"),
+    ("toml", "application/toml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("rs", "text/x-rust"),
+    ("py", "text/x-python"),
+    ("nix", "text/x-nix"),
+];
+
+fn detect_mime(path: &Path) -> Option<String> {
+    if let Ok(Some(kind)) = infer::get_from_path(path) {
+        return Some(kind.mime_type().to_string());
     }
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    MIME_EXT_FALLBACK
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, mime)| mime.to_string())
+}
 
-    fn process_op(&self, op: DbOp) {
-        match op {
-            DbOp::SaveFile(entry) => self.save_file(&entry),
-            DbOp::RemoveFile(path) => self.remove_file(&path),
-            DbOp::SaveBookmark(bookmark) => self.save_bookmark(&bookmark),
-            DbOp::ClearFilesUnder(path) => self.clear_files_under(&path),
-        }
+/// Dispatches to a format-specific extractor based on the detected MIME
+/// type. `FILE_MIME` is always set when detection succeeds, even if the
+/// format-specific pass below finds nothing else to add.
+fn extract_attributes(path: &Path) -> Option<HashMap<String, AttrValue>> {
+    let mime = detect_mime(path)?;
+    let mut attrs = HashMap::new();
+    attrs.insert("FILE_MIME".to_string(), AttrValue::Text(mime.clone()));
+
+    if mime.starts_with("audio/") {
+        extract_audio_tags(path, &mut attrs);
+    } else if mime.starts_with("image/") {
+        extract_image_exif(path, &mut attrs);
+    } else if mime == "application/pdf" {
+        extract_pdf_info(path, &mut attrs);
     }
+
+    Some(attrs)
 }
 
-/// Start database thread that processes operations from a channel
-fn start_db_thread(db: Database) -> Sender<DbOp> {
-    let (tx, rx) = channel::<DbOp>();
+fn extract_audio_tags(path: &Path, attrs: &mut HashMap<String, AttrValue>) {
+    let Ok(tagged_file) = Probe::open(path).and_then(|probe| probe.read()) else { return };
 
-    thread::spawn(move || {
-        for op in rx {
-            db.process_op(op);
-        }
-    });
+    attrs.insert(
+        "AUDIO_DURATION_SECS".to_string(),
+        AttrValue::Number(tagged_file.properties().duration().as_secs_f64()),
+    );
 
-    tx
+    let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) else { return };
+
+    if let Some(title) = tag.title() {
+        attrs.insert("AUDIO_TITLE".to_string(), AttrValue::Text(title.to_string()));
+    }
+    if let Some(artist) = tag.artist() {
+        attrs.insert("AUDIO_ARTIST".to_string(), AttrValue::Text(artist.to_string()));
+    }
+    if let Some(album) = tag.album() {
+        attrs.insert("AUDIO_ALBUM".to_string(), AttrValue::Text(album.to_string()));
+    }
+    if let Some(year) = tag.year() {
+        attrs.insert("AUDIO_YEAR".to_string(), AttrValue::Number(year as f64));
+    }
 }
 
-// ============================================================================
-// File Scanner
-// ============================================================================
+fn extract_image_exif(path: &Path, attrs: &mut HashMap<String, AttrValue>) {
+    let Ok(file) = fs::File::open(path) else { return };
+    let mut reader = std::io::BufReader::new(file);
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut reader) else { return };
 
-fn is_network_mount(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
+    if let Some(field) = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY) {
+        attrs.insert("IMAGE_TAKEN_AT".to_string(), AttrValue::Text(field.display_value().to_string()));
+    }
+    if let Some(field) = exif.get_field(exif::Tag::Make, exif::In::PRIMARY) {
+        attrs.insert("IMAGE_CAMERA_MAKE".to_string(), AttrValue::Text(field.display_value().to_string()));
+    }
+    if let Some(field) = exif.get_field(exif::Tag::Model, exif::In::PRIMARY) {
+        attrs.insert("IMAGE_CAMERA_MODEL".to_string(), AttrValue::Text(field.display_value().to_string()));
+    }
+}
 
-    if path_str.starts_with("/mnt/") ||
-       path_str.starts_with("/media/") ||
-       path_str.starts_with("/net/") {
-        if let Ok(mounts) = fs::read_to_string("/proc/mounts") {
-            for line in mounts.lines() {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 3 {
-                    let mount_point = parts[1];
-                    let fs_type = parts[2];
+fn extract_pdf_info(path: &Path, attrs: &mut HashMap<String, AttrValue>) {
+    let Ok(doc) = lopdf::Document::load(path) else { return };
 
-                    if path_str.starts_with(mount_point) {
-                        return matches!(fs_type, "nfs" | "nfs4" | "cifs" | "smb" | "smbfs" | "fuse.sshfs");
-                    }
-                }
+    attrs.insert("PDF_PAGE_COUNT".to_string(), AttrValue::Number(doc.get_pages().len() as f64));
+
+    if let Ok(info) = doc.trailer.get(b"Info").and_then(|obj| obj.as_reference()) {
+        if let Ok(info_dict) = doc.get_dictionary(info) {
+            if let Ok(title) = info_dict.get(b"Title").and_then(|obj| obj.as_str()) {
+                attrs.insert(
+                    "PDF_TITLE".to_string(),
+                    AttrValue::Text(String::from_utf8_lossy(title).to_string()),
+                );
             }
         }
     }
-    false
 }
 
-fn should_exclude(path: &Path) -> bool {
-    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-        // Exact match or prefix match for .Trash-* folders
-        EXCLUDE_PATTERNS.iter().any(|p| name == *p || (p == &".Trash" && name.starts_with(".Trash")))
+/// Maps a detected MIME type to the broken-file check it should receive, or
+/// `None` if we don't have a lightweight structural check for it. Mirrors the
+/// `extract_attributes` MIME dispatch above.
+fn classify_broken_candidate(mime: &str) -> Option<&'static str> {
+    if mime.starts_with("image/") {
+        Some("image")
+    } else if mime == "application/pdf" {
+        Some("pdf")
+    } else if mime == "application/zip" {
+        Some("zip")
+    } else if mime.starts_with("audio/") {
+        Some("audio")
     } else {
-        false
+        None
     }
 }
 
-fn get_mtime(path: &Path) -> i64 {
-    path.metadata()
-        .and_then(|m| m.modified())
-        .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64)
-        .unwrap_or(0)
-}
+/// ZIP End Of Central Directory record signature. A well-formed ZIP always
+/// has one somewhere in its last ~64KiB (the comment field is capped at
+/// 65535 bytes); its absence means a truncated or rewritten central
+/// directory, czkawka's canonical "broken ZIP" case.
+const ZIP_EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4B, 0x05, 0x06];
+const ZIP_EOCD_SEARCH_WINDOW: u64 = 66_000;
+
+/// JPEG End Of Image marker. A JPEG that doesn't end with it was cut off
+/// mid-write - the classic truncated-download case.
+const JPEG_EOI_MARKER: [u8; 2] = [0xFF, 0xD9];
+
+fn check_image_health(path: &Path) -> Result<(), String> {
+    let kind = infer::get_from_path(path)
+        .map_err(|e| format!("read error: {}", e))?
+        .ok_or_else(|| "not a recognized image format".to_string())?;
+
+    if kind.mime_type() == "image/jpeg" {
+        let mut file = fs::File::open(path).map_err(|e| format!("open error: {}", e))?;
+        let len = file.metadata().map_err(|e| format!("stat error: {}", e))?.len();
+        if len < 2 {
+            return Err("file too short to contain a JPEG EOI marker".to_string());
+        }
+        file.seek(std::io::SeekFrom::End(-2)).map_err(|e| format!("seek error: {}", e))?;
+        let mut tail = [0u8; 2];
+        file.read_exact(&mut tail).map_err(|e| format!("read error: {}", e))?;
+        if tail != JPEG_EOI_MARKER {
+            return Err("missing JPEG end-of-image marker (truncated file)".to_string());
+        }
+    }
 
-fn get_size(path: &Path) -> u64 {
-    path.metadata().map(|m| m.len()).unwrap_or(0)
+    Ok(())
 }
 
-fn scan_directory(
-    root: &Path,
-    index: &Arc<RwLock<TrigramIndex>>,
-    db_tx: &Sender<DbOp>,
-) -> usize {
-    let mut count = 0;
-    let start = std::time::Instant::now();
+fn check_pdf_health(path: &Path) -> Result<(), String> {
+    lopdf::Document::load(path).map(|_| ()).map_err(|e| format!("{}", e))
+}
 
-    info!("Scanning directory: {}", root.display());
+fn check_zip_health(path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("open error: {}", e))?;
+    let len = file.metadata().map_err(|e| format!("stat error: {}", e))?.len();
+    let window = ZIP_EOCD_SEARCH_WINDOW.min(len);
+    file.seek(std::io::SeekFrom::End(-(window as i64))).map_err(|e| format!("seek error: {}", e))?;
+    let mut buf = vec![0u8; window as usize];
+    file.read_exact(&mut buf).map_err(|e| format!("read error: {}", e))?;
 
-    let walker = WalkDir::new(root)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| !should_exclude(e.path()));
+    if buf.windows(4).any(|w| w == ZIP_EOCD_SIGNATURE) {
+        Ok(())
+    } else {
+        Err("missing end-of-central-directory record".to_string())
+    }
+}
 
-    for entry in walker.filter_map(|e| e.ok()) {
-        let path = entry.path();
-        let path_str = path.to_string_lossy().to_string();
-        let is_dir = entry.file_type().is_dir();
-        let mtime = get_mtime(path);
-        let size = if is_dir { 0 } else { get_size(path) };
-
-        let id = {
-            let mut idx = index.write().unwrap();
-            idx.add(path_str.clone(), is_dir, mtime, size)
-        };
+fn check_audio_health(path: &Path) -> Result<(), String> {
+    Probe::open(path)
+        .and_then(|probe| probe.read())
+        .map(|_| ())
+        .map_err(|e| format!("{}", e))
+}
 
-        let entry = FileEntry { id, path: path_str, is_dir, mtime, size };
-        let _ = db_tx.send(DbOp::SaveFile(entry));
-        count += 1;
+/// Lightweight structural validation for `type_of_file` (as classified by
+/// `classify_broken_candidate`) - a header/structure parse, not a full
+/// decode, matching czkawka's `broken_files` approach.
+fn check_file_health(path: &Path, type_of_file: &str) -> Result<(), String> {
+    match type_of_file {
+        "image" => check_image_health(path),
+        "pdf" => check_pdf_health(path),
+        "zip" => check_zip_health(path),
+        "audio" => check_audio_health(path),
+        other => Err(format!("no health check for type {}", other)),
     }
+}
 
-    let elapsed = start.elapsed();
-    info!("Scanned {} files in {:?}", count, elapsed);
+const EXTRACTOR_POOL_THREADS: usize = 2;
+
+/// Runs metadata extraction off the hot scan path on its own small rayon
+/// pool, so slow EXIF/tag/PDF parsing can never stall a scan or the
+/// inotify watcher.
+struct ExtractorPool {
+    pool: rayon::ThreadPool,
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
+}
+
+impl ExtractorPool {
+    fn new(index: Arc<AsyncRwLock<TrigramIndex>>, db_tx: mpsc::UnboundedSender<DbOp>) -> Self {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(EXTRACTOR_POOL_THREADS)
+            .thread_name(|i| format!("extractor-{}", i))
+            .build()
+            .expect("Failed to build extractor pool");
+        ExtractorPool { pool, index, db_tx }
+    }
 
-    count
+    /// Queue a file for extraction. Non-blocking; the result is applied to
+    /// the index and persisted whenever the pool gets to it.
+    fn submit(&self, id: u32, path: String) {
+        let index = self.index.clone();
+        let db_tx = self.db_tx.clone();
+        self.pool.spawn(move || {
+            let Some(attrs) = extract_attributes(Path::new(&path)) else { return };
+            if attrs.is_empty() {
+                return;
+            }
+            index.blocking_write().attrs.insert(id, attrs.clone());
+            let _ = db_tx.send(DbOp::SaveAttributes { path, attrs });
+        });
+    }
 }
 
 // ============================================================================
@@ -663,20 +2339,23 @@ fn scan_directory(
 
 fn start_watcher(
     paths: Vec<PathBuf>,
-    index: Arc<RwLock<TrigramIndex>>,
-    db_tx: Sender<DbOp>,
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
+    config: Arc<Config>,
+    extractor: Arc<ExtractorPool>,
 ) -> notify::Result<RecommendedWatcher> {
     let index_clone = index.clone();
     let db_tx_clone = db_tx.clone();
+    let config_clone = config.clone();
 
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<Event, notify::Error>| {
             match res {
-                Ok(event) => handle_fs_event(event, &index_clone, &db_tx_clone),
+                Ok(event) => handle_fs_event(event, &index_clone, &db_tx_clone, &config_clone, &extractor),
                 Err(e) => warn!("Watch error: {:?}", e),
             }
         },
-        Config::default().with_poll_interval(Duration::from_secs(2)),
+        notify::Config::default().with_poll_interval(Duration::from_secs(2)),
     )?;
 
     for path in paths {
@@ -691,13 +2370,13 @@ fn start_watcher(
     Ok(watcher)
 }
 
-fn handle_fs_event(event: Event, index: &Arc<RwLock<TrigramIndex>>, db_tx: &Sender<DbOp>) {
+fn handle_fs_event(event: Event, index: &Arc<AsyncRwLock<TrigramIndex>>, db_tx: &mpsc::UnboundedSender<DbOp>, config: &Config, extractor: &ExtractorPool) {
     use notify::EventKind::*;
 
     match event.kind {
         Create(_) | Modify(_) => {
             for path in event.paths {
-                if should_exclude(&path) {
+                if should_exclude(&path, config) {
                     continue;
                 }
                 if let Ok(meta) = path.metadata() {
@@ -705,16 +2384,41 @@ fn handle_fs_event(event: Event, index: &Arc<RwLock<TrigramIndex>>, db_tx: &Send
                     let is_dir = meta.is_dir();
                     let mtime = get_mtime(&path);
                     let size = if is_dir { 0 } else { meta.len() };
+                    let fingerprint = if is_dir { None } else { compute_fingerprint(&path, size) };
+
+                    // A Create right after a Remove of a file with the same
+                    // fingerprint is recognized and logged as a move/rename.
+                    // The Remove branch below has already dropped the old
+                    // entry from the index, so `rename` finds nothing under
+                    // the old path and the file is re-added via `add` under a
+                    // new ID; the old entry is not updated in place.
+                    let moved_from = fingerprint.and_then(|fp| {
+                        let mut idx = index.blocking_write();
+                        idx.pending_removals.remove(&fp)
+                    });
 
                     let id = {
-                        let mut idx = index.write().unwrap();
-                        idx.add(path_str.clone(), is_dir, mtime, size)
+                        let mut idx = index.blocking_write();
+                        match &moved_from {
+                            Some(old_path) => idx
+                                .rename(old_path, path_str.clone(), mtime, size)
+                                .unwrap_or_else(|| idx.add(path_str.clone(), is_dir, mtime, size, fingerprint)),
+                            None => idx.add(path_str.clone(), is_dir, mtime, size, fingerprint),
+                        }
                     };
 
-                    let entry = FileEntry { id, path: path_str, is_dir, mtime, size };
+                    let entry = FileEntry { id, path: path_str, is_dir, mtime, size, fingerprint };
                     let _ = db_tx.send(DbOp::SaveFile(entry));
 
-                    debug!("Indexed: {}", path.display());
+                    if !is_dir {
+                        extractor.submit(id, path.to_string_lossy().to_string());
+                    }
+
+                    if let Some(old_path) = &moved_from {
+                        info!("Detected move: {} -> {}", old_path, path.display());
+                    } else {
+                        debug!("Indexed: {}", path.display());
+                    }
                 }
             }
         }
@@ -723,7 +2427,16 @@ fn handle_fs_event(event: Event, index: &Arc<RwLock<TrigramIndex>>, db_tx: &Send
                 let path_str = path.to_string_lossy().to_string();
 
                 {
-                    let mut idx = index.write().unwrap();
+                    let mut idx = index.blocking_write();
+                    let fingerprint = idx
+                        .path_to_id
+                        .get(&path_str)
+                        .and_then(|id| idx.files.get(id))
+                        .and_then(|e| e.fingerprint);
+                    if let Some(fp) = fingerprint {
+                        prune_pending_removals(&mut idx.pending_removals);
+                        idx.pending_removals.insert(fp, path_str.clone());
+                    }
                     idx.remove(&path_str);
                 }
 
@@ -736,14 +2449,225 @@ fn handle_fs_event(event: Event, index: &Arc<RwLock<TrigramIndex>>, db_tx: &Send
     }
 }
 
+// ============================================================================
+// Task Store (persisted scan/task progress)
+// ============================================================================
+
+/// Coarse phase of a running scan, nested inside `TaskStatus::Processing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStage {
+    Walking,
+    Indexing,
+    Persisting,
+}
+
+/// Status of a task tracked by `TaskStore`, modeled after MeiliSearch's task
+/// queue and czkawka's `ProgressData`. `Processing` carries the same
+/// `files_checked`/`files_to_check` counters clients poll for a progress bar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum TaskStatus {
+    Enqueued,
+    Processing { stage: JobStage, files_checked: usize, files_to_check: usize },
+    Succeeded { indexed: usize },
+    /// Flagged via `CANCEL`. `indexed` is the count `scan_directory` returned;
+    /// a scan cancelled before persisting indexes and persists none of its
+    /// entries, while one flagged after that point ran to completion.
+    Cancelled { indexed: usize },
+    Failed { msg: String },
+}
+
+impl TaskStatus {
+    fn is_terminal(&self) -> bool {
+        matches!(self, TaskStatus::Succeeded { .. } | TaskStatus::Cancelled { .. } | TaskStatus::Failed { .. })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TaskReport {
+    id: u64,
+    /// Human-readable description, e.g. `"rescan:/mnt/media"`.
+    kind: String,
+    status: TaskStatus,
+    started_at: i64,
+}
+
+/// Shared cancel/pause flags for a single task, held by both the `TaskStore`
+/// entry and the worker task actually running the scan.
+struct JobControl {
+    cancel: AtomicBool,
+    paused: AtomicBool,
+}
+
+impl JobControl {
+    fn new() -> Self {
+        JobControl { cancel: AtomicBool::new(false), paused: AtomicBool::new(false) }
+    }
+}
+
+struct TaskEntry {
+    report: TaskReport,
+    control: Arc<JobControl>,
+}
+
+/// Cap on in-memory task entries; once hit, finished tasks (`Succeeded` /
+/// `Cancelled` / `Failed`) are dropped from memory to make room, the same way
+/// `MAX_PENDING_REMOVALS` bounds `pending_removals`. The durable history
+/// still lives in the `tasks` table.
+const MAX_TASKS_IN_MEMORY: usize = 200;
+
+/// Tracks in-flight and recently-finished tasks (scans, bookmark adds) so
+/// clients can poll progress (`TASK`/`TASKS`) instead of blocking on a
+/// synchronous reply, and recover task history across a daemon restart via
+/// `load_from`.
+struct TaskStore {
+    tasks: RwLock<HashMap<u64, TaskEntry>>,
+    next_id: AtomicU64,
+}
+
+impl TaskStore {
+    fn new() -> Self {
+        TaskStore { tasks: RwLock::new(HashMap::new()), next_id: AtomicU64::new(1) }
+    }
+
+    fn register(&self, kind: String) -> (u64, Arc<JobControl>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let control = Arc::new(JobControl::new());
+        let report = TaskReport {
+            id,
+            kind,
+            status: TaskStatus::Enqueued,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+        };
+
+        let mut tasks = self.tasks.write().unwrap();
+        if tasks.len() >= MAX_TASKS_IN_MEMORY {
+            tasks.retain(|_, e| !e.report.status.is_terminal());
+        }
+        tasks.insert(id, TaskEntry { report, control: control.clone() });
+        (id, control)
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut TaskReport)) -> Option<TaskReport> {
+        let mut tasks = self.tasks.write().unwrap();
+        let entry = tasks.get_mut(&id)?;
+        f(&mut entry.report);
+        Some(entry.report.clone())
+    }
+
+    fn get(&self, id: u64) -> Option<TaskReport> {
+        self.tasks.read().unwrap().get(&id).map(|e| e.report.clone())
+    }
+
+    fn finish(&self, id: u64, indexed: usize, cancelled: bool) -> Option<TaskReport> {
+        self.update(id, |r| {
+            r.status =
+                if cancelled { TaskStatus::Cancelled { indexed } } else { TaskStatus::Succeeded { indexed } }
+        })
+    }
+
+    fn list(&self) -> Vec<TaskReport> {
+        self.tasks.read().unwrap().values().map(|e| e.report.clone()).collect()
+    }
+
+    fn cancel(&self, id: u64) -> bool {
+        match self.tasks.read().unwrap().get(&id) {
+            Some(entry) => {
+                entry.control.cancel.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restore persisted task history after a restart. Loaded entries are
+    /// inert (a fresh, never-triggered `JobControl`), but remain queryable
+    /// via `TASK`/`TASKS` so a client can see what happened before the
+    /// daemon restarted.
+    fn load_from(&self, reports: Vec<TaskReport>) {
+        let mut tasks = self.tasks.write().unwrap();
+        let mut max_id = 0;
+        for report in reports {
+            max_id = max_id.max(report.id);
+            tasks.insert(report.id, TaskEntry { report, control: Arc::new(JobControl::new()) });
+        }
+        if max_id >= self.next_id.load(Ordering::SeqCst) {
+            self.next_id.store(max_id + 1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Handle used to report progress on and check control signals for a single
+/// registered task. `None` everywhere `scan_directory` isn't tracked as a
+/// task, so the task-store machinery stays opt-in. Cheap to `Clone` (all
+/// fields are `Arc`/`u64`), which `scan_directory` relies on to move an owned
+/// copy into the blocking task that does the actual walking.
+#[derive(Clone)]
+struct ScanJob {
+    manager: Arc<TaskStore>,
+    id: u64,
+    control: Arc<JobControl>,
+}
+
+impl ScanJob {
+    fn set_stage(&self, stage: JobStage) {
+        self.manager.update(self.id, |r| {
+            r.status = match r.status {
+                TaskStatus::Processing { files_checked, files_to_check, .. } => {
+                    TaskStatus::Processing { stage, files_checked, files_to_check }
+                }
+                _ => TaskStatus::Processing { stage, files_checked: 0, files_to_check: 0 },
+            };
+        });
+    }
+
+    fn set_totals(&self, to_check: usize) {
+        self.manager.update(self.id, |r| {
+            if let TaskStatus::Processing { files_to_check, .. } = &mut r.status {
+                *files_to_check = to_check;
+            }
+        });
+    }
+
+    fn tick(&self, checked: usize) {
+        self.manager.update(self.id, |r| {
+            if let TaskStatus::Processing { files_checked, .. } = &mut r.status {
+                *files_checked = checked;
+            }
+        });
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.control.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Spin-wait while paused. Scans are I/O-bound, so a short sleep between
+    /// checks costs nothing of substance.
+    fn wait_if_paused(&self) {
+        while self.control.paused.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    fn finish(&self, indexed: usize) -> Option<TaskReport> {
+        self.manager.finish(self.id, indexed, self.is_cancelled())
+    }
+}
+
 // ============================================================================
 // Network Mount Scanner (periodic)
 // ============================================================================
 
 fn start_network_scanner(
     paths: Vec<PathBuf>,
-    index: Arc<RwLock<TrigramIndex>>,
-    db_tx: Sender<DbOp>,
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
+    config: Arc<Config>,
+    task_store: Arc<TaskStore>,
+    extractor: Arc<ExtractorPool>,
 ) {
     let network_paths: Vec<PathBuf> = paths.into_iter()
         .filter(|p| is_network_mount(p))
@@ -753,13 +2677,23 @@ fn start_network_scanner(
         return;
     }
 
-    thread::spawn(move || {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.network_scan_interval_secs));
         loop {
+            interval.tick().await;
             for path in &network_paths {
                 info!("Periodic scan of network mount: {}", path.display());
-                scan_directory(path, &index, &db_tx);
+                let (task_id, control) = task_store.register(format!("periodic:{}", path.display()));
+                if let Some(report) = task_store.get(task_id) {
+                    let _ = db_tx.send(DbOp::SaveTask(report));
+                }
+                let job = ScanJob { manager: task_store.clone(), id: task_id, control };
+                let count = scan_directory(path, &index, &db_tx, &config, Some(&job), Some(extractor.as_ref())).await;
+                if let Some(report) = job.finish(count) {
+                    let _ = db_tx.send(DbOp::SaveTask(report));
+                }
             }
-            thread::sleep(Duration::from_secs(NETWORK_SCAN_INTERVAL_SECS));
+            regenerate_snapshot(&index).await;
         }
     });
 }
@@ -772,18 +2706,19 @@ const INTEGRITY_CHECK_INTERVAL_SECS: u64 = 60;  // Check every minute
 const INTEGRITY_BATCH_SIZE: usize = 5000;       // Files per check cycle
 
 fn start_integrity_checker(
-    index: Arc<RwLock<TrigramIndex>>,
-    db_tx: Sender<DbOp>,
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
 ) {
-    thread::spawn(move || {
+    tokio::spawn(async move {
         let mut offset = 0;
+        let mut interval = tokio::time::interval(Duration::from_secs(INTEGRITY_CHECK_INTERVAL_SECS));
 
         loop {
-            thread::sleep(Duration::from_secs(INTEGRITY_CHECK_INTERVAL_SECS));
+            interval.tick().await;
 
             // Get a batch of file paths to check
             let paths_to_check: Vec<String> = {
-                let idx = index.read().unwrap();
+                let idx = index.read().await;
                 let all_paths: Vec<_> = idx.files.values()
                     .map(|f| f.path.clone())
                     .collect();
@@ -803,23 +2738,145 @@ fn start_integrity_checker(
                 batch
             };
 
-            // Check which files no longer exist
+            // `path.exists()` is a blocking syscall; run the whole batch on
+            // the blocking pool rather than one stat() per iteration on the
+            // async executor (same discipline as `start_health_checker`).
+            let missing: Vec<String> = tokio::task::spawn_blocking(move || {
+                paths_to_check
+                    .into_iter()
+                    .filter(|path_str| !Path::new(path_str).exists())
+                    .collect()
+            })
+            .await
+            .unwrap_or_default();
+
+            // Remove entries for files that no longer exist
             let mut removed_count = 0;
-            for path_str in paths_to_check {
-                let path = Path::new(&path_str);
-                if !path.exists() {
-                    // File was deleted - remove from index
-                    {
-                        let mut idx = index.write().unwrap();
-                        idx.remove(&path_str);
+            let mut moved_count = 0;
+            for path_str in missing {
+                {
+                    let mut idx = index.write().await;
+                    // If this path's fingerprint already lives under a
+                    // different path we're currently indexing, the file
+                    // wasn't deleted - a scan or watcher event already
+                    // picked up its new location. Just drop the stale
+                    // source entry rather than logging it as a deletion.
+                    let fingerprint = idx
+                        .path_to_id
+                        .get(&path_str)
+                        .and_then(|id| idx.files.get(id))
+                        .and_then(|e| e.fingerprint);
+                    let move_target = fingerprint.and_then(|fp| idx.find_by_fingerprint(fp, &path_str));
+
+                    idx.remove(&path_str);
+
+                    if let Some(target) = move_target {
+                        info!("Integrity check: {} moved to {}", path_str, target);
+                        moved_count += 1;
                     }
-                    let _ = db_tx.send(DbOp::RemoveFile(path_str));
-                    removed_count += 1;
                 }
+                let _ = db_tx.send(DbOp::RemoveFile(path_str));
+                removed_count += 1;
             }
 
             if removed_count > 0 {
-                info!("Integrity check: removed {} stale entries", removed_count);
+                info!(
+                    "Integrity check: removed {} stale entries ({} recognized as moves)",
+                    removed_count, moved_count
+                );
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Health Checker (detects corrupt/broken files, opt-in)
+// ============================================================================
+
+const HEALTH_CHECK_INTERVAL_SECS: u64 = 300;  // Check every 5 minutes
+const HEALTH_CHECK_BATCH_SIZE: usize = 500;   // Files per check cycle
+
+/// Opt-in periodic pass that classifies files by MIME type and runs a
+/// lightweight structural check on the ones we know how to validate (see
+/// `classify_broken_candidate`/`check_file_health`), ported from czkawka's
+/// `broken_files` feature. Results are cached in `TrigramIndex.health` keyed
+/// by `(size, mtime)` so an unchanged file is only ever validated once.
+fn start_health_checker(
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
+) {
+    tokio::spawn(async move {
+        let mut offset = 0;
+        let mut interval = tokio::time::interval(Duration::from_secs(HEALTH_CHECK_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            // Gather a batch of (id, path, size, mtime) for files that either
+            // haven't been checked yet or have changed since the last check.
+            // This is pure in-memory bookkeeping against `idx.health` - no
+            // file I/O, so it's fine to do under the read lock; the MIME
+            // sniffing and structural check happen off-lock below.
+            let batch: Vec<(u32, String, u64, i64)> = {
+                let idx = index.read().await;
+                let unchecked: Vec<_> = idx.files.values()
+                    .filter(|f| !f.is_dir)
+                    .filter(|f| match idx.health.get(&f.id) {
+                        Some(h) => h.checked_size != f.size || h.checked_mtime != f.mtime,
+                        None => true,
+                    })
+                    .map(|f| (f.id, f.path.clone(), f.size, f.mtime))
+                    .collect();
+
+                if unchecked.is_empty() {
+                    offset = 0;
+                    continue;
+                }
+
+                if offset >= unchecked.len() {
+                    offset = 0;
+                }
+
+                let end = (offset + HEALTH_CHECK_BATCH_SIZE).min(unchecked.len());
+                let batch = unchecked[offset..end].to_vec();
+                offset = end;
+                batch
+            };
+
+            // MIME detection and the structural check both do blocking file
+            // I/O, so the whole batch runs on the blocking pool with no lock
+            // held, mirroring `ExtractorPool::submit`.
+            let results: Vec<(u32, String, FileHealth)> = tokio::task::spawn_blocking(move || {
+                batch.into_iter()
+                    .filter_map(|(id, path_str, size, mtime)| {
+                        let mime = detect_mime(Path::new(&path_str))?;
+                        let type_of_file = classify_broken_candidate(&mime)?.to_string();
+                        let error_string = check_file_health(Path::new(&path_str), &type_of_file).err();
+                        let health = FileHealth { type_of_file, error_string, checked_size: size, checked_mtime: mtime };
+                        Some((id, path_str, health))
+                    })
+                    .collect()
+            }).await.unwrap_or_default();
+
+            let mut checked_count = 0;
+            let mut broken_count = 0;
+            if !results.is_empty() {
+                let mut idx = index.write().await;
+                for (id, path_str, health) in results {
+                    if health.error_string.is_some() {
+                        broken_count += 1;
+                    }
+                    idx.health.insert(id, health.clone());
+                    let _ = db_tx.send(DbOp::SaveFileHealth { path: path_str, health });
+                    checked_count += 1;
+                }
+            }
+
+            if checked_count > 0 {
+                info!(
+                    "Health check: validated {} files ({} newly flagged as broken)",
+                    checked_count, broken_count
+                );
             }
         }
     });
@@ -829,17 +2886,20 @@ fn start_integrity_checker(
 // IPC Server
 // ============================================================================
 
-fn handle_client(
+async fn handle_client(
     stream: UnixStream,
-    index: &Arc<RwLock<TrigramIndex>>,
-    db_tx: &Sender<DbOp>,
+    index: &Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: &mpsc::UnboundedSender<DbOp>,
+    config: &Config,
+    task_store: &Arc<TaskStore>,
+    extractor: &Arc<ExtractorPool>,
 ) {
-    let mut reader = BufReader::new(&stream);
-    let mut writer = &stream;
+    let (read_half, mut write_half) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
 
     loop {
         let mut line = String::new();
-        match reader.read_line(&mut line) {
+        match reader.read_line(&mut line).await {
             Ok(0) => break,
             Ok(_) => {
                 let line = line.trim();
@@ -855,9 +2915,9 @@ fn handle_client(
                     match serde_json::from_str::<SearchAllRequest>(json) {
                         Ok(req) => {
                             let start = std::time::Instant::now();
-                            let results = index.read().unwrap().search_all(&req);
+                            let results = index.read().await.search_all(&req);
                             let elapsed = start.elapsed().as_millis() as u64;
-                            let total = index.read().unwrap().file_count();
+                            let total = index.read().await.file_count();
 
                             // Return as JSON with results array
                             let resp = serde_json::json!({
@@ -874,9 +2934,9 @@ fn handle_client(
                     match serde_json::from_str::<SearchRequest>(json) {
                         Ok(req) => {
                             let start = std::time::Instant::now();
-                            let results = index.read().unwrap().search(&req);
+                            let results = index.read().await.search(&req);
                             let elapsed = start.elapsed().as_millis() as u64;
-                            let total = index.read().unwrap().file_count();
+                            let total = index.read().await.file_count();
 
                             let resp = SearchResponse {
                                 results,
@@ -894,17 +2954,33 @@ fn handle_client(
                             let path = PathBuf::from(&bookmark.path);
 
                             let _ = db_tx.send(DbOp::SaveBookmark(bookmark.clone()));
+                            index.write().await.bookmarks.push(bookmark);
+
+                            let (task_id, control) = task_store.register(format!("add_bookmark:{}", path.display()));
+                            if let Some(report) = task_store.get(task_id) {
+                                let _ = db_tx.send(DbOp::SaveTask(report));
+                            }
+
+                            let task_store = task_store.clone();
+                            let index = index.clone();
+                            let db_tx = db_tx.clone();
+                            let config = config.clone();
+                            let extractor = extractor.clone();
+                            tokio::spawn(async move {
+                                let job = ScanJob { manager: task_store.clone(), id: task_id, control };
+                                let count = scan_directory(&path, &index, &db_tx, &config, Some(&job), Some(extractor.as_ref())).await;
+                                regenerate_snapshot(&index).await;
+                                if let Some(report) = job.finish(count) {
+                                    let _ = db_tx.send(DbOp::SaveTask(report));
+                                }
+                            });
 
-                            let count = scan_directory(&path, index, db_tx);
-
-                            index.write().unwrap().bookmarks.push(bookmark);
-
-                            format!(r#"{{"status": "ok", "indexed": {}}}"#, count)
+                            format!(r#"{{"task_id": {}}}"#, task_id)
                         }
                         Err(e) => format!(r#"{{"error": "{}"}}"#, e),
                     }
                 } else if line == "STATS" {
-                    let idx = index.read().unwrap();
+                    let idx = index.read().await;
                     format!(
                         r#"{{"files": {}, "trigrams": {}, "bookmarks": {}}}"#,
                         idx.files.len(),
@@ -912,30 +2988,93 @@ fn handle_client(
                         idx.bookmarks.len()
                     )
                 } else if line.starts_with("RESCAN ") {
-                    let path = line[7..].trim();
-                    let path_buf = PathBuf::from(path);
+                    // Returns a task id immediately and runs the scan on a
+                    // background task so clients can poll TASK/TASKS for
+                    // progress instead of holding this socket open.
+                    let path = line[7..].trim().to_string();
+                    let path_buf = PathBuf::from(&path);
 
                     {
-                        let mut idx = index.write().unwrap();
+                        let mut idx = index.write().await;
                         let to_remove: Vec<String> = idx.files.values()
-                            .filter(|f| f.path.starts_with(path))
+                            .filter(|f| f.path.starts_with(&path))
                             .map(|f| f.path.clone())
                             .collect();
                         for p in to_remove {
                             idx.remove(&p);
                         }
                     }
-                    let _ = db_tx.send(DbOp::ClearFilesUnder(path.to_string()));
+                    let _ = db_tx.send(DbOp::ClearFilesUnder(path.clone()));
+
+                    let (task_id, control) = task_store.register(format!("rescan:{}", path));
+                    if let Some(report) = task_store.get(task_id) {
+                        let _ = db_tx.send(DbOp::SaveTask(report));
+                    }
 
-                    let count = scan_directory(&path_buf, index, db_tx);
-                    format!(r#"{{"status": "ok", "indexed": {}}}"#, count)
+                    let task_store = task_store.clone();
+                    let index = index.clone();
+                    let db_tx = db_tx.clone();
+                    let config = config.clone();
+                    let extractor = extractor.clone();
+                    tokio::spawn(async move {
+                        let job = ScanJob { manager: task_store.clone(), id: task_id, control };
+                        let count = scan_directory(&path_buf, &index, &db_tx, &config, Some(&job), Some(extractor.as_ref())).await;
+                        regenerate_snapshot(&index).await;
+                        if let Some(report) = job.finish(count) {
+                            let _ = db_tx.send(DbOp::SaveTask(report));
+                        }
+                    });
+
+                    format!(r#"{{"task_id": {}}}"#, task_id)
+                } else if line.starts_with("FIND_DUPLICATES ") {
+                    let json = &line[16..];
+                    match serde_json::from_str::<FindDuplicatesRequest>(json) {
+                        Ok(req) => {
+                            let groups = find_duplicates(index, db_tx, &req, config).await;
+                            serde_json::to_string(&groups).unwrap_or_else(|_| "[]".to_string())
+                        }
+                        Err(e) => format!(r#"{{"error": "{}"}}"#, e),
+                    }
+                } else if line.starts_with("TASK ") {
+                    match line[5..].trim().parse::<u64>() {
+                        Ok(id) => match task_store.get(id) {
+                            Some(report) => serde_json::to_string(&report).unwrap_or_else(|_| "{}".to_string()),
+                            None => r#"{"error": "not_found"}"#.to_string(),
+                        },
+                        Err(e) => format!(r#"{{"error": "{}"}}"#, e),
+                    }
+                } else if line == "TASKS" {
+                    let mut tasks = task_store.list();
+                    tasks.sort_by(|a, b| b.id.cmp(&a.id));
+                    tasks.truncate(50);
+                    serde_json::to_string(&tasks).unwrap_or_else(|_| "[]".to_string())
+                } else if line.starts_with("CANCEL ") {
+                    match line[7..].trim().parse::<u64>() {
+                        Ok(id) => {
+                            let ok = task_store.cancel(id);
+                            format!(r#"{{"status": "{}"}}"#, if ok { "ok" } else { "not_found" })
+                        }
+                        Err(e) => format!(r#"{{"error": "{}"}}"#, e),
+                    }
+                } else if line == "LIST_BROKEN" {
+                    let idx = index.read().await;
+                    let broken: Vec<BrokenFileEntry> = idx.health.iter()
+                        .filter_map(|(id, health)| {
+                            let error_string = health.error_string.clone()?;
+                            let path = idx.files.get(id)?.path.clone();
+                            Some(BrokenFileEntry { path, type_of_file: health.type_of_file.clone(), error_string })
+                        })
+                        .collect();
+                    serde_json::to_string(&broken).unwrap_or_else(|_| "[]".to_string())
                 } else if line == "PING" {
                     r#"{"status": "pong"}"#.to_string()
                 } else {
                     r#"{"error": "unknown command"}"#.to_string()
                 };
 
-                if let Err(e) = writeln!(writer, "{}", response) {
+                let mut response = response;
+                response.push('\n');
+                if let Err(e) = write_half.write_all(response.as_bytes()).await {
                     warn!("Failed to write response: {}", e);
                     break;
                 }
@@ -948,10 +3087,16 @@ fn handle_client(
     }
 }
 
-fn start_server(index: Arc<RwLock<TrigramIndex>>, db_tx: Sender<DbOp>) {
-    let _ = fs::remove_file(SOCKET_PATH);
+async fn start_server(
+    index: Arc<AsyncRwLock<TrigramIndex>>,
+    db_tx: mpsc::UnboundedSender<DbOp>,
+    config: Arc<Config>,
+    task_store: Arc<TaskStore>,
+    extractor: Arc<ExtractorPool>,
+) {
+    let _ = fs::remove_file(&config.socket_path);
 
-    let listener = match UnixListener::bind(SOCKET_PATH) {
+    let listener = match UnixListener::bind(&config.socket_path) {
         Ok(l) => l,
         Err(e) => {
             error!("Failed to bind socket: {}", e);
@@ -959,15 +3104,18 @@ fn start_server(index: Arc<RwLock<TrigramIndex>>, db_tx: Sender<DbOp>) {
         }
     };
 
-    info!("Listening on {}", SOCKET_PATH);
+    info!("Listening on {}", config.socket_path);
 
-    for stream in listener.incoming() {
-        match stream {
-            Ok(stream) => {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _addr)) => {
                 let index = index.clone();
                 let db_tx = db_tx.clone();
-                thread::spawn(move || {
-                    handle_client(stream, &index, &db_tx);
+                let config = config.clone();
+                let task_store = task_store.clone();
+                let extractor = extractor.clone();
+                tokio::spawn(async move {
+                    handle_client(stream, &index, &db_tx, &config, &task_store, &extractor).await;
                 });
             }
             Err(e) => {
@@ -981,7 +3129,8 @@ fn start_server(index: Arc<RwLock<TrigramIndex>>, db_tx: Sender<DbOp>) {
 // Main
 // ============================================================================
 
-fn main() {
+#[tokio::main]
+async fn main() {
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
@@ -991,8 +3140,10 @@ fn main() {
 
     info!("NixNav Daemon starting...");
 
+    let config = Arc::new(load_config());
+
     // Open database and load into index
-    let db = match Database::open() {
+    let db = match Database::open(&config) {
         Ok(db) => db,
         Err(e) => {
             error!("Failed to open database: {}", e);
@@ -1000,20 +3151,53 @@ fn main() {
         }
     };
 
-    let index = Arc::new(RwLock::new(TrigramIndex::new()));
+    let index = Arc::new(AsyncRwLock::new(TrigramIndex::new(config.clone())));
 
     let start = std::time::Instant::now();
     let loaded = {
-        let mut idx = index.write().unwrap();
-        db.load_into_index(&mut idx).unwrap_or(0)
+        let mut idx = index.write().await;
+        match load_snapshot(&mut idx) {
+            Some(count) => {
+                info!("Loaded {} files from mmapped snapshot in {:?}", count, start.elapsed());
+                if let Err(e) = db.load_bookmarks_into(&mut idx) {
+                    warn!("Failed to load bookmarks: {}", e);
+                }
+                match db.load_attributes_into(&mut idx) {
+                    Ok(n) => info!("Loaded {} extracted attribute sets", n),
+                    Err(e) => warn!("Failed to load attributes: {}", e),
+                }
+                match db.load_file_health_into(&mut idx) {
+                    Ok(n) => info!("Loaded {} file health records", n),
+                    Err(e) => warn!("Failed to load file health: {}", e),
+                }
+                count
+            }
+            None => {
+                let count = db.load_into_index(&mut idx).unwrap_or(0);
+                info!("Loaded {} files from database in {:?}", count, start.elapsed());
+                match db.load_attributes_into(&mut idx) {
+                    Ok(n) => info!("Loaded {} extracted attribute sets", n),
+                    Err(e) => warn!("Failed to load attributes: {}", e),
+                }
+                match db.load_file_health_into(&mut idx) {
+                    Ok(n) => info!("Loaded {} file health records", n),
+                    Err(e) => warn!("Failed to load file health: {}", e),
+                }
+                count
+            }
+        }
     };
-    info!("Loaded {} files from database in {:?}", loaded, start.elapsed());
+
+    let persisted_tasks = db.load_tasks().unwrap_or_else(|e| {
+        warn!("Failed to load task history: {}", e);
+        Vec::new()
+    });
 
     // Start database thread
     let db_tx = start_db_thread(db);
 
     // Default bookmark if none exist
-    let bookmarks = index.read().unwrap().bookmarks.clone();
+    let bookmarks = index.read().await.bookmarks.clone();
     let paths: Vec<PathBuf> = if bookmarks.is_empty() {
         let home = dirs::home_dir().expect("No home directory");
         info!("No bookmarks found, using home: {}", home.display());
@@ -1022,22 +3206,33 @@ fn main() {
         bookmarks.iter().map(|b| PathBuf::from(&b.path)).collect()
     };
 
+    let extractor = Arc::new(ExtractorPool::new(index.clone(), db_tx.clone()));
+
     // Initial scan if database was empty
     if loaded == 0 {
         for path in &paths {
-            scan_directory(path, &index, &db_tx);
+            scan_directory(path, &index, &db_tx, &config, None, Some(extractor.as_ref())).await;
         }
+        regenerate_snapshot(&index).await;
     }
 
+    let task_store = Arc::new(TaskStore::new());
+    task_store.load_from(persisted_tasks);
+
     // Start file watcher for local paths
-    let _watcher = start_watcher(paths.clone(), index.clone(), db_tx.clone());
+    let _watcher = start_watcher(paths.clone(), index.clone(), db_tx.clone(), config.clone(), extractor.clone());
 
     // Start periodic scanner for network mounts
-    start_network_scanner(paths, index.clone(), db_tx.clone());
+    start_network_scanner(paths, index.clone(), db_tx.clone(), config.clone(), task_store.clone(), extractor.clone());
 
     // Start integrity checker (detects deleted files missed by inotify)
     start_integrity_checker(index.clone(), db_tx.clone());
 
+    // Start corrupt/broken-file health checker, opt-in via daemon.conf
+    if config.health_check_enabled {
+        start_health_checker(index.clone(), db_tx.clone());
+    }
+
     // Start IPC server (blocks)
-    start_server(index, db_tx);
+    start_server(index, db_tx, config, task_store, extractor).await;
 }